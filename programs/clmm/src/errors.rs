@@ -1,24 +1,102 @@
 use anchor_lang::prelude::*;
 
+//one enum for the whole program so no two failures share a code
 #[error_code]
-pub enum TickMathError {
+pub enum ClmmError {
+    //math
+    #[msg("multiplication or division overflow")]
+    MulDivOverflow,
+
+    #[msg("division by zero")]
+    DivisionByZero,
+
+    #[msg("token amount does not fit in u64")]
+    AmountOverflow,
+
+    #[msg("sqrt price moved out of range")]
+    SqrtPriceOutOfRange,
+
+    #[msg("arithmetic overflow")]
+    MathOverflow,
+
+    #[msg("arithmetic underflow")]
+    MathUnderflow,
+
+    //tick validation
     #[msg("tick is higher then eligible amount")]
     TickUpperOverflow,
-}
 
-#[error_code]
-pub enum LiquidityError {
+    #[msg("tick is lower then eligible amount")]
+    TickLowerOverflow,
+
+    #[msg("lower tick must be below upper tick")]
+    TickInvalidOrder,
+
+    #[msg("tick is not a multiple of tick spacing")]
+    TickAndSpacingNotMatch,
+
+    #[msg("tick array start index is not aligned to the pool tick spacing")]
+    InvalidStartIndex,
+
+    #[msg("tick is not covered by the tick array")]
+    TickNotInArray,
+
+    #[msg("not enough tick arrays supplied to complete the swap")]
+    NotEnoughTickArrays,
+
+    #[msg("tick arrays must be adjacent and ordered in the swap direction")]
+    InvalidTickArraySequence,
+
+    //account mismatch
+    #[msg("account belongs to a different pool")]
+    PoolMismatch,
+
+    #[msg("tick array is not at its canonical address")]
+    InvalidTickArrayAddress,
+
+    #[msg("account does not match the one recorded in state")]
+    AccountMismatch,
+
+    #[msg("pool was created under a different amm config")]
+    AmmConfigMismatch,
+
+    #[msg("reward mint or vault does not match the reward slot")]
+    InvalidRewardAccount,
+
+    #[msg("token_0_mint must sort before token_1_mint")]
+    MintsNotCanonical,
+
+    //pool and config parameters
+    #[msg("initial sqrt price is outside the supported range")]
+    InvalidSqrtPrice,
+
+    #[msg("tick spacing must be greater than zero")]
+    InvalidTickSpacing,
+
+    #[msg("fee rate must be below the fee denominator")]
+    InvalidFeeRate,
+
+    #[msg("protocol fee rate exceeds the fee denominator")]
+    InvalidProtocolFeeRate,
+
+    #[msg("mint has a token extension the pool does not support")]
+    UnsupportedMintExtension,
+
+    #[msg("mint transfer hook program is not on the config allowlist")]
+    UnsupportedTransferHook,
+
+    #[msg("too many transfer hook programs for the allowlist")]
+    TransferHookAllowlistFull,
+
+    //liquidity and swap amounts
     #[msg("zero Liquidity Error")]
     ZeroLiquidity,
 
     #[msg("insufficient liquidity")]
     InsufficientLiquidity,
-}
 
-#[error_code]
-pub enum SwapError {
-    #[msg("invalid square root price limit")]
-    InvalidSqrtPriceLimit,
+    #[msg("position still holds liquidity or owed tokens")]
+    PositionNotEmpty,
 
     #[msg("no liquidity available in pool")]
     NoLiquidity,
@@ -26,7 +104,55 @@ pub enum SwapError {
     #[msg("swap amount must be greater than zero")]
     ZeroAmount,
 
+    #[msg("swap amount exceeds the supported range")]
+    AmountTooLarge,
+
+    //slippage
+    #[msg("price slippage check failed")]
+    PriceSlippageCheck,
+
+    #[msg("invalid square root price limit")]
+    InvalidSqrtPriceLimit,
+
     #[msg("output amount less than minimum")]
     TooLittleOutputReceived,
-}
 
+    #[msg("input amount more than maximum")]
+    TooMuchInputPaid,
+
+    //authority
+    #[msg("signer is not authorized for this action")]
+    InvalidAuthority,
+
+    #[msg("signer does not own the position")]
+    NotPositionOwner,
+
+    #[msg("position nft token account is required")]
+    MissingPositionNft,
+
+    #[msg("unknown delegate permission bits")]
+    InvalidDelegatePermissions,
+
+    #[msg("withdrawals must go to accounts owned by the position owner")]
+    RecipientNotOwner,
+
+    //rewards
+    #[msg("reward index out of range or not the next free slot")]
+    InvalidRewardIndex,
+
+    #[msg("reward slot is not initialized")]
+    RewardNotInitialized,
+
+    #[msg("reward mint cannot be one of the pool mints")]
+    RewardMintIsPoolMint,
+
+    //oracle
+    #[msg("cardinality exceeds the observation capacity")]
+    InvalidObservationCardinality,
+
+    #[msg("requested time is older than the oldest observation")]
+    ObservationTooOld,
+
+    #[msg("twap window must be longer than zero seconds")]
+    InvalidTwapWindow,
+}
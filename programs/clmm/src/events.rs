@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+
+use crate::states::config::MAX_TRANSFER_HOOK_PROGRAMS;
+
+//events are emitted through a self cpi so they survive log truncation
+
+/// Emitted when a pool is created
+#[event]
+pub struct PoolCreated {
+    pub pool_state: Pubkey,
+    pub amm_config: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub tick_spacing: u16,
+    pub fee_rate: u32,
+    pub sqrt_price_x64: u128,
+    pub tick: i32,
+}
+
+/// Emitted when a position is opened, nft_mint is default for plain positions
+#[event]
+pub struct PositionOpened {
+    pub pool_state: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+}
+
+/// Emitted when liquidity is added to a position
+/// amounts are what the vaults received
+#[event]
+pub struct LiquidityIncreased {
+    pub pool_state: Pubkey,
+    pub position: Pubkey,
+    pub liquidity: u128,
+    pub amount_0: u64,
+    pub amount_1: u64,
+    pub position_liquidity: u128,
+}
+
+/// Emitted when liquidity is removed from a position
+/// amounts are credited to the position and paid out by collect_fees
+#[event]
+pub struct LiquidityDecreased {
+    pub pool_state: Pubkey,
+    pub position: Pubkey,
+    pub liquidity: u128,
+    pub amount_0: u64,
+    pub amount_1: u64,
+    pub position_liquidity: u128,
+}
+
+/// Emitted on every swap, amounts are vault side
+/// fees are charged in the input token
+#[event]
+pub struct SwapEvent {
+    pub pool_state: Pubkey,
+    pub sender: Pubkey,
+    pub zero_for_one: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub sqrt_price_x64_before: u128,
+    pub sqrt_price_x64_after: u128,
+    pub tick_before: i32,
+    pub tick_after: i32,
+    pub liquidity_before: u128,
+    pub liquidity_after: u128,
+    pub fee_amount: u64,
+    pub protocol_fee: u64,
+}
+
+/// Emitted when owed tokens are paid out of a position
+#[event]
+pub struct FeesCollected {
+    pub pool_state: Pubkey,
+    pub position: Pubkey,
+    pub recipient_token_0_account: Pubkey,
+    pub recipient_token_1_account: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when a position is closed, its rent goes to recipient
+#[event]
+pub struct PositionClosed {
+    pub pool_state: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+}
+
+/// Emitted when a position delegate is set, or revoked with a default delegate
+#[event]
+pub struct DelegateUpdated {
+    pub position: Pubkey,
+    pub delegate: Pubkey,
+    pub permissions: u8,
+}
+
+/// Emitted when owed rewards are paid out of a position
+#[event]
+pub struct RewardCollected {
+    pub pool_state: Pubkey,
+    pub position: Pubkey,
+    pub reward_index: u8,
+    pub reward_mint: Pubkey,
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the admin withdraws accrued protocol fees
+#[event]
+pub struct ProtocolFeesCollected {
+    pub pool_state: Pubkey,
+    pub recipient_token_0_account: Pubkey,
+    pub recipient_token_1_account: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when a reward slot is opened on a pool
+#[event]
+pub struct RewardInitialized {
+    pub pool_state: Pubkey,
+    pub reward_index: u8,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+}
+
+/// Emitted when a reward's emission rate changes
+#[event]
+pub struct RewardEmissionsUpdated {
+    pub pool_state: Pubkey,
+    pub reward_index: u8,
+    pub emissions_per_second_x64: u128,
+}
+
+/// Emitted when the admin creates a fee tier
+#[event]
+pub struct AmmConfigCreated {
+    pub amm_config: Pubkey,
+    pub index: u16,
+    pub tick_spacing: u16,
+    pub trade_fee_rate: u32,
+    pub protocol_fee_rate: u32,
+}
+
+/// Emitted when a fee tier's rates change, they apply to the next swap of every pool in it
+#[event]
+pub struct AmmConfigUpdated {
+    pub amm_config: Pubkey,
+    pub trade_fee_rate: u32,
+    pub protocol_fee_rate: u32,
+}
+
+/// Emitted when a fee tier's transfer hook allowlist is replaced, default entries are unused
+#[event]
+pub struct TransferHookAllowlistUpdated {
+    pub amm_config: Pubkey,
+    pub transfer_hook_programs: [Pubkey; MAX_TRANSFER_HOOK_PROGRAMS],
+}
+
+/// Emitted when a tick array is created for a pool
+#[event]
+pub struct TickArrayInitialized {
+    pub pool_state: Pubkey,
+    pub tick_array: Pubkey,
+    pub start_tick_index: i32,
+}
+
+/// Emitted when the oracle buffer is asked to grow, old equals new when it was already larger
+#[event]
+pub struct ObservationCardinalityIncreased {
+    pub pool_state: Pubkey,
+    pub cardinality_next_old: u16,
+    pub cardinality_next_new: u16,
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::ClmmError,
+    events::PositionClosed,
+    states::{pool::LpPoolStateShape, tick::TickArrayState, Position},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    //position owner
+    pub signer: Signer<'info>,
+
+    /// CHECK: any account chosen by the owner to receive the rent
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    //pool state
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //position, closed to the recipient
+    #[account(mut, close = recipient,
+    constraint = position.pool_id == pool_state_account.key() @ ClmmError::PoolMismatch)]
+    pub position: Account<'info, Position>,
+
+    //tick arrays holding the position bounds
+    #[account(mut)]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+    #[account(mut)]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    //nft accounts, only for nft positions, the token is burned on close
+    #[account(mut)]
+    pub position_nft_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut)]
+    pub position_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+impl<'info> ClosePosition<'info> {
+    pub fn close_position(&mut self) -> Result<PositionClosed> {
+        //only the owner may close, delegates cannot
+        let owner = self
+            .position
+            .get_owner(self.position_nft_account.as_deref())?;
+        require_keys_eq!(owner, self.signer.key(), ClmmError::NotPositionOwner);
+
+        //nothing may be left behind in the position
+        require!(
+            self.position.liquidity == 0
+                && self.position.tokens_owed_0 == 0
+                && self.position.tokens_owed_1 == 0
+                && self
+                    .position
+                    .reward_infos
+                    .iter()
+                    .all(|reward_info| reward_info.amount_owed == 0),
+            ClmmError::PositionNotEmpty
+        );
+
+        //validate the tick arrays
+        let pool_key = self.pool_state_account.key();
+        let tick_spacing = self.pool_state_account.tick_spacing;
+        {
+            let mut tick_array_lower = self.tick_array_lower.load_mut()?;
+            tick_array_lower.validate(&self.tick_array_lower.key(), &pool_key)?;
+            let lower_tick_index =
+                tick_array_lower.get_tick_offset(self.position.tick_lower, tick_spacing)?;
+
+            //release the bound once no liquidity references it
+            tick_array_lower.ticks[lower_tick_index].clear_if_unreferenced();
+        }
+
+        // Arrays may be the same account when both bounds share one array
+        {
+            let mut tick_array_upper = self.tick_array_upper.load_mut()?;
+            tick_array_upper.validate(&self.tick_array_upper.key(), &pool_key)?;
+            let upper_tick_index =
+                tick_array_upper.get_tick_offset(self.position.tick_upper, tick_spacing)?;
+            tick_array_upper.ticks[upper_tick_index].clear_if_unreferenced();
+        }
+
+        if self.position.is_nft() {
+            self.burn_position_nft()?;
+        }
+
+        Ok(PositionClosed {
+            pool_state: pool_key,
+            position: self.position.key(),
+            owner,
+            recipient: self.recipient.key(),
+        })
+    }
+
+    //burn the position token and refund its account rent to the recipient
+    fn burn_position_nft(&self) -> Result<()> {
+        let (Some(nft_mint), Some(nft_account), Some(token_program)) = (
+            &self.position_nft_mint,
+            &self.position_nft_account,
+            &self.token_program,
+        ) else {
+            return err!(ClmmError::MissingPositionNft);
+        };
+
+        let cpi_accounts_burn = Burn {
+            mint: nft_mint.to_account_info(),
+            from: nft_account.to_account_info(),
+            authority: self.signer.to_account_info(),
+        };
+        let cpi_ctx_burn = CpiContext::new(token_program.to_account_info(), cpi_accounts_burn);
+        token_interface::burn(cpi_ctx_burn, 1)?;
+
+        let cpi_accounts_close = CloseAccount {
+            account: nft_account.to_account_info(),
+            destination: self.recipient.to_account_info(),
+            authority: self.signer.to_account_info(),
+        };
+        let cpi_ctx_close = CpiContext::new(token_program.to_account_info(), cpi_accounts_close);
+        token_interface::close_account(cpi_ctx_close)
+    }
+}
@@ -0,0 +1,167 @@
+use std::cmp::min;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    errors::ClmmError,
+    events::FeesCollected,
+    libraries::update_position_fees,
+    states::{pool::LpPoolStateShape, tick::TickArrayState, Position, PERMISSION_COLLECT},
+    utils::transfer_checked_with_hook,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    //position owner
+    pub signer: Signer<'info>,
+
+    //pool state
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //token program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    //mint for the tokens
+    #[account(address = pool_state_account.token_0_mint @ ClmmError::AccountMismatch)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = pool_state_account.token_1_mint @ ClmmError::AccountMismatch)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    //recipient accounts chosen by the owner
+    #[account(mut, token::mint = token_0_mint)]
+    pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_1_mint)]
+    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //vaults account
+    #[account(mut, address = pool_state_account.token_0_vault_address @ ClmmError::AccountMismatch)]
+    pub token_0_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = pool_state_account.token_1_vault_address @ ClmmError::AccountMismatch)]
+    pub token_1_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //position
+    #[account(mut,    constraint = position.pool_id == pool_state_account.key() @ ClmmError::PoolMismatch)]
+    pub position: Account<'info, Position>,
+
+    //token account holding the position nft, only for nft positions
+    pub position_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //tick arrays holding the position bounds
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+impl<'info> CollectFees<'info> {
+    pub fn collect_fees(
+        &mut self,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<FeesCollected> {
+        let owner = self.position.check_authority(
+            &self.signer.key(),
+            self.position_nft_account.as_deref(),
+            PERMISSION_COLLECT,
+        )?;
+
+        //a delegate can only pay out to the owner's own accounts
+        if owner != self.signer.key() {
+            require!(
+                self.recipient_token_0_account.owner == owner
+                    && self.recipient_token_1_account.owner == owner,
+                ClmmError::RecipientNotOwner
+            );
+        }
+
+        //load the tick state from the tick array
+        let pool_key = self.pool_state_account.key();
+        let tick_lower_state = self.tick_array_lower.load()?;
+        let tick_upper_state = self.tick_array_upper.load()?;
+        tick_lower_state.validate(&self.tick_array_lower.key(), &pool_key)?;
+        tick_upper_state.validate(&self.tick_array_upper.key(), &pool_key)?;
+
+        //calculate the index in the array
+        let tick_spacing = self.pool_state_account.tick_spacing;
+        let lower_tick_index =
+            tick_lower_state.get_tick_offset(self.position.tick_lower, tick_spacing)?;
+        let upper_tick_index =
+            tick_upper_state.get_tick_offset(self.position.tick_upper, tick_spacing)?;
+
+        //get the tick states
+        let lower_tick = tick_lower_state.ticks[lower_tick_index];
+        let upper_tick = tick_upper_state.ticks[upper_tick_index];
+        drop(tick_lower_state);
+        drop(tick_upper_state);
+
+        //bring owed fees up to date before paying out
+        update_position_fees(
+            &mut self.position,
+            &self.pool_state_account,
+            &lower_tick,
+            &upper_tick,
+        )?;
+
+        //pay out at most what is owed, withdrawn principal and fees alike
+        let amount_0 = min(amount_0_requested, self.position.tokens_owed_0);
+        let amount_1 = min(amount_1_requested, self.position.tokens_owed_1);
+        self.position.tokens_owed_0 = self
+            .position
+            .tokens_owed_0
+            .checked_sub(amount_0)
+            .ok_or(error!(ClmmError::MathUnderflow))?;
+        self.position.tokens_owed_1 = self
+            .position
+            .tokens_owed_1
+            .checked_sub(amount_1)
+            .ok_or(error!(ClmmError::MathUnderflow))?;
+
+        //transfer tokens from vault to recipient (requires PDA signer)
+        let seeds = self.pool_state_account.seeds();
+        let signer_seeds = &[&seeds[..]];
+
+        // Transfer token0 from vault to recipient
+        if amount_0 > 0 {
+            let cpi_accounts_0 = TransferChecked {
+                from: self.token_0_vault_account.to_account_info(),
+                to: self.recipient_token_0_account.to_account_info(),
+                authority: self.pool_state_account.to_account_info(),
+                mint: self.token_0_mint.to_account_info(),
+            };
+            let cpi_ctx_0 = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts_0,
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_0, amount_0, self.token_0_mint.decimals)?;
+        }
+
+        // Transfer token1 from vault to recipient
+        if amount_1 > 0 {
+            let cpi_accounts_1 = TransferChecked {
+                from: self.token_1_vault_account.to_account_info(),
+                to: self.recipient_token_1_account.to_account_info(),
+                authority: self.pool_state_account.to_account_info(),
+                mint: self.token_1_mint.to_account_info(),
+            };
+            let cpi_ctx_1 = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts_1,
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_1, amount_1, self.token_1_mint.decimals)?;
+        }
+
+        Ok(FeesCollected {
+            pool_state: self.pool_state_account.key(),
+            position: self.position.key(),
+            recipient_token_0_account: self.recipient_token_0_account.key(),
+            recipient_token_1_account: self.recipient_token_1_account.key(),
+            amount_0,
+            amount_1,
+        })
+    }
+}
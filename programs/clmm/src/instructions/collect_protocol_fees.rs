@@ -0,0 +1,113 @@
+use std::cmp::min;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::ClmmError;
+use crate::events::ProtocolFeesCollected;
+use crate::states::{config::AmmConfig, pool::LpPoolStateShape};
+use crate::utils::transfer_checked_with_hook;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    //config admin
+    pub owner: Signer<'info>,
+
+    //fee tier the pool was created from
+    #[account(has_one = owner @ ClmmError::InvalidAuthority)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    //pool state
+    #[account(mut, has_one = amm_config @ ClmmError::AmmConfigMismatch)]
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //token program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    //mint for the tokens
+    #[account(address = pool_state_account.token_0_mint @ ClmmError::AccountMismatch)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = pool_state_account.token_1_mint @ ClmmError::AccountMismatch)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    //recipient accounts chosen by the admin
+    #[account(mut, token::mint = token_0_mint)]
+    pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_1_mint)]
+    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //vaults account
+    #[account(mut, address = pool_state_account.token_0_vault_address @ ClmmError::AccountMismatch)]
+    pub token_0_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = pool_state_account.token_1_vault_address @ ClmmError::AccountMismatch)]
+    pub token_1_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'info> CollectProtocolFees<'info> {
+    pub fn collect_protocol_fees(
+        &mut self,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<ProtocolFeesCollected> {
+        //only the protocol accumulators are paid out, lp owed balances stay in the vaults
+        let pool = &mut self.pool_state_account;
+        let amount_0 = min(amount_0_requested, pool.protocol_fees_token_0);
+        let amount_1 = min(amount_1_requested, pool.protocol_fees_token_1);
+        pool.protocol_fees_token_0 = pool
+            .protocol_fees_token_0
+            .checked_sub(amount_0)
+            .ok_or(error!(ClmmError::MathUnderflow))?;
+        pool.protocol_fees_token_1 = pool
+            .protocol_fees_token_1
+            .checked_sub(amount_1)
+            .ok_or(error!(ClmmError::MathUnderflow))?;
+
+        //transfer tokens from vault to recipient (requires PDA signer)
+        let seeds = self.pool_state_account.seeds();
+        let signer_seeds = &[&seeds[..]];
+
+        // Transfer token0 from vault to recipient
+        if amount_0 > 0 {
+            let cpi_accounts_0 = TransferChecked {
+                from: self.token_0_vault_account.to_account_info(),
+                to: self.recipient_token_0_account.to_account_info(),
+                authority: self.pool_state_account.to_account_info(),
+                mint: self.token_0_mint.to_account_info(),
+            };
+            let cpi_ctx_0 = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts_0,
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_0, amount_0, self.token_0_mint.decimals)?;
+        }
+
+        // Transfer token1 from vault to recipient
+        if amount_1 > 0 {
+            let cpi_accounts_1 = TransferChecked {
+                from: self.token_1_vault_account.to_account_info(),
+                to: self.recipient_token_1_account.to_account_info(),
+                authority: self.pool_state_account.to_account_info(),
+                mint: self.token_1_mint.to_account_info(),
+            };
+            let cpi_ctx_1 = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts_1,
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_1, amount_1, self.token_1_mint.decimals)?;
+        }
+
+        Ok(ProtocolFeesCollected {
+            pool_state: self.pool_state_account.key(),
+            recipient_token_0_account: self.recipient_token_0_account.key(),
+            recipient_token_1_account: self.recipient_token_1_account.key(),
+            amount_0,
+            amount_1,
+        })
+    }
+}
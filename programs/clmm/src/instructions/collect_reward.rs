@@ -0,0 +1,161 @@
+use std::cmp::min;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    errors::ClmmError,
+    events::RewardCollected,
+    libraries::update_position_rewards,
+    states::{
+        pool::{LpPoolStateShape, REWARD_NUM},
+        tick::TickArrayState,
+        Position, PERMISSION_COLLECT,
+    },
+    utils::transfer_checked_with_hook,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CollectReward<'info> {
+    //position owner
+    pub signer: Signer<'info>,
+
+    //pool state, rewards are accrued before paying out
+    #[account(mut)]
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //token program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    //reward mint and vault, checked against the reward slot
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = reward_token_mint, token::authority = pool_state_account)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //recipient account chosen by the owner
+    #[account(mut, token::mint = reward_token_mint)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //position
+    #[account(mut,    constraint = position.pool_id == pool_state_account.key() @ ClmmError::PoolMismatch)]
+    pub position: Account<'info, Position>,
+
+    //token account holding the position nft, only for nft positions
+    pub position_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //tick arrays holding the position bounds
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+impl<'info> CollectReward<'info> {
+    pub fn collect_reward(
+        &mut self,
+        reward_index: u8,
+        amount_requested: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<RewardCollected> {
+        let owner = self.position.check_authority(
+            &self.signer.key(),
+            self.position_nft_account.as_deref(),
+            PERMISSION_COLLECT,
+        )?;
+
+        //a delegate can only pay out to the owner's own accounts
+        if owner != self.signer.key() {
+            require!(
+                self.recipient_token_account.owner == owner,
+                ClmmError::RecipientNotOwner
+            );
+        }
+
+        //validate the reward slot
+        let reward_index = reward_index as usize;
+        require!(reward_index < REWARD_NUM, ClmmError::InvalidRewardIndex);
+        let reward_info = self.pool_state_account.reward_infos[reward_index];
+        require!(reward_info.initialized(), ClmmError::RewardNotInitialized);
+        require_keys_eq!(
+            reward_info.mint,
+            self.reward_token_mint.key(),
+            ClmmError::InvalidRewardAccount
+        );
+        require_keys_eq!(
+            reward_info.vault,
+            self.reward_vault.key(),
+            ClmmError::InvalidRewardAccount
+        );
+
+        //accrue emissions up to now
+        self.pool_state_account
+            .update_reward_infos(Clock::get()?.unix_timestamp as u64)?;
+
+        //load the tick state from the tick array
+        let pool_key = self.pool_state_account.key();
+        let tick_lower_state = self.tick_array_lower.load()?;
+        let tick_upper_state = self.tick_array_upper.load()?;
+        tick_lower_state.validate(&self.tick_array_lower.key(), &pool_key)?;
+        tick_upper_state.validate(&self.tick_array_upper.key(), &pool_key)?;
+
+        //calculate the index in the array
+        let tick_spacing = self.pool_state_account.tick_spacing;
+        let lower_tick_index =
+            tick_lower_state.get_tick_offset(self.position.tick_lower, tick_spacing)?;
+        let upper_tick_index =
+            tick_upper_state.get_tick_offset(self.position.tick_upper, tick_spacing)?;
+
+        //get the tick states
+        let lower_tick = tick_lower_state.ticks[lower_tick_index];
+        let upper_tick = tick_upper_state.ticks[upper_tick_index];
+        drop(tick_lower_state);
+        drop(tick_upper_state);
+
+        //bring owed rewards up to date before paying out
+        update_position_rewards(
+            &mut self.position,
+            &self.pool_state_account,
+            &lower_tick,
+            &upper_tick,
+        )?;
+
+        //pay out at most what is owed and what the vault holds
+        let position_reward = &mut self.position.reward_infos[reward_index];
+        let amount = min(
+            min(amount_requested, position_reward.amount_owed),
+            self.reward_vault.amount,
+        );
+        position_reward.amount_owed = position_reward
+            .amount_owed
+            .checked_sub(amount)
+            .ok_or(error!(ClmmError::MathUnderflow))?;
+
+        //transfer tokens from vault to recipient (requires PDA signer), nothing to do when nothing is owed
+        if amount > 0 {
+            let seeds = self.pool_state_account.seeds();
+            let signer_seeds = &[&seeds[..]];
+
+            let cpi_accounts = TransferChecked {
+                from: self.reward_vault.to_account_info(),
+                to: self.recipient_token_account.to_account_info(),
+                authority: self.pool_state_account.to_account_info(),
+                mint: self.reward_token_mint.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx, amount, self.reward_token_mint.decimals)?;
+        }
+
+        Ok(RewardCollected {
+            pool_state: self.pool_state_account.key(),
+            position: self.position.key(),
+            reward_index: reward_index as u8,
+            reward_mint: self.reward_token_mint.key(),
+            recipient_token_account: self.recipient_token_account.key(),
+            amount,
+        })
+    }
+}
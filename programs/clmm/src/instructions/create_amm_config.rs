@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::admin;
+use crate::errors::ClmmError;
+use crate::events::AmmConfigCreated;
+use crate::libraries::FEE_RATE_DENOMINATOR;
+use crate::states::config::{AmmConfig, AMM_CONFIG_SEED, MAX_TRANSFER_HOOK_PROGRAMS};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreateAmmConfig<'info> {
+    //protocol admin
+    #[account(mut, address = admin::ID @ ClmmError::InvalidAuthority)]
+    pub owner: Signer<'info>,
+
+    //fee tier
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AmmConfig::INIT_SPACE,
+        seeds = [AMM_CONFIG_SEED, &index.to_le_bytes()],
+        bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    //system program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateAmmConfig<'info> {
+    pub fn handler(
+        &mut self,
+        index: u16,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        bumps: &CreateAmmConfigBumps,
+    ) -> Result<AmmConfigCreated> {
+        //validate the fee tier
+        require!(tick_spacing > 0, ClmmError::InvalidTickSpacing);
+        require!(
+            trade_fee_rate < FEE_RATE_DENOMINATOR,
+            ClmmError::InvalidFeeRate
+        );
+        require!(
+            protocol_fee_rate <= FEE_RATE_DENOMINATOR,
+            ClmmError::InvalidProtocolFeeRate
+        );
+
+        let amm_config = &mut self.amm_config;
+        amm_config.bump = bumps.amm_config;
+        amm_config.index = index;
+        amm_config.owner = self.owner.key();
+        amm_config.trade_fee_rate = trade_fee_rate;
+        amm_config.protocol_fee_rate = protocol_fee_rate;
+        amm_config.tick_spacing = tick_spacing;
+        amm_config.transfer_hook_programs = [Pubkey::default(); MAX_TRANSFER_HOOK_PROGRAMS];
+
+        Ok(AmmConfigCreated {
+            amm_config: amm_config.key(),
+            index,
+            tick_spacing,
+            trade_fee_rate,
+            protocol_fee_rate,
+        })
+    }
+}
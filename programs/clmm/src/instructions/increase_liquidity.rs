@@ -1,27 +1,20 @@
-use std::cmp::min;
-
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self},
-    token_interface::{self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
-};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
-use crate::errors::LiquidityError;
+use crate::errors::ClmmError;
+use crate::events::LiquidityIncreased;
 use crate::{
     libraries::{
-        get_amounts_0_from_liquidity, get_amounts_1_from_liquidity, get_fee_growth_above,
-        get_fee_growth_below, get_liquidity_from_amount_0, get_liquidity_from_amount_1,
-        get_sqrt_price_at_tick, liquidity_math,
+        get_amount_0_delta, get_amount_1_delta, get_sqrt_price_at_tick, update_position_fees,
+        update_position_rewards,
     },
     states::{
-        pool::LpPoolStateShape,
-        position,
-        tick::{self, TickArrayState},
-        Position,
+        pool::LpPoolStateShape, tick::TickArrayState, Position, PERMISSION_INCREASE_LIQUIDITY,
     },
+    utils::{get_transfer_inverse_fee, transfer_checked_with_hook},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct IncreaseLiquidity<'info> {
     //signer
@@ -36,7 +29,9 @@ pub struct IncreaseLiquidity<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 
     //mint for the tokens
+    #[account(address = pool_state_account.token_0_mint @ ClmmError::AccountMismatch)]
     pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = pool_state_account.token_1_mint @ ClmmError::AccountMismatch)]
     pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
     //user accounts
@@ -46,17 +41,19 @@ pub struct IncreaseLiquidity<'info> {
     pub user_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     //vaults account
-    #[account(mut, token::mint = token_0_mint, token::authority = pool_state_account)]
+    #[account(mut, address = pool_state_account.token_0_vault_address @ ClmmError::AccountMismatch)]
     pub token_0_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
-    #[account(mut, token::mint = token_1_mint, token::authority = pool_state_account)]
+    #[account(mut, address = pool_state_account.token_1_vault_address @ ClmmError::AccountMismatch)]
     pub token_1_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     //position
     //can pass the constrain this way
-    #[account(mut,    constraint = position.owner == signer.key(),
-    constraint = position.pool_id == pool_state_account.key() )]
+    #[account(mut,    constraint = position.pool_id == pool_state_account.key() @ ClmmError::PoolMismatch)]
     pub position: Account<'info, Position>,
 
+    //token account holding the position nft, only for nft positions
+    pub position_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     //tick array upper
     //used account loader
     #[account(mut)]
@@ -70,124 +67,91 @@ impl<'info> IncreaseLiquidity<'info> {
     pub fn increase_liquidity(
         &mut self,
         liquidity: u128,
-        token_0_amount: u64,
-        token_1_amount: u64,
-    ) -> Result<()> {
+        amount_0_max: u64,
+        amount_1_max: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<LiquidityIncreased> {
+        self.position.check_authority(
+            &self.signer.key(),
+            self.position_nft_account.as_deref(),
+            PERMISSION_INCREASE_LIQUIDITY,
+        )?;
+
+        //accrue rewards before liquidity changes
+        self.pool_state_account
+            .update_reward_infos(Clock::get()?.unix_timestamp as u64)?;
+
         //read from the accounts
-        let current_price = self.pool_state_account.sqrt_price_x64;
         let current_tick = self.pool_state_account.tick_current;
         let lower_position = self.position.tick_lower;
         let upper_position = self.position.tick_upper;
-        let liquidity_current = self.position.liquidity;
         let sqrt_price_lower = get_sqrt_price_at_tick(self.position.tick_lower);
         let sqrt_price_upper = get_sqrt_price_at_tick(self.position.tick_upper);
         let sqrt_price_current = self.pool_state_account.sqrt_price_x64;
 
-        let mut liquidity: u128;
-        let mut amount_0: u64;
-        let mut amount_1: u64;
-
-        //right now just single function will segregate this in future
-        //there are 3 cases
-        //case 1 : current_price is below position range
-        if current_tick < lower_position {
-            //get liquidity from account 0 function called
-            liquidity =
-                get_liquidity_from_amount_0(sqrt_price_lower, sqrt_price_upper, token_0_amount);
-            amount_0 = token_0_amount;
-            amount_1 = 0;
+        //validate liquidity
+        require!(liquidity > 0, ClmmError::ZeroLiquidity);
+
+        //tokens needed for the target liquidity, rounded up in favour of the pool
+        //below range only token 0, above range only token 1, in range both
+        let (amount_0, amount_1) = if current_tick < lower_position {
+            (
+                get_amount_0_delta(sqrt_price_lower, sqrt_price_upper, liquidity, true)?,
+                0,
+            )
         } else if current_tick >= upper_position {
-            liquidity =
-                get_liquidity_from_amount_1(sqrt_price_lower, sqrt_price_upper, token_1_amount);
-            amount_0 = 0;
-            amount_1 = token_1_amount;
+            (
+                0,
+                get_amount_1_delta(sqrt_price_lower, sqrt_price_upper, liquidity, true)?,
+            )
         } else {
-            let liquidity_0 =
-                get_liquidity_from_amount_0(sqrt_price_current, sqrt_price_upper, token_0_amount);
-            let liquidity_1 =
-                get_liquidity_from_amount_1(sqrt_price_lower, sqrt_price_current, token_1_amount);
-            liquidity = min(liquidity_1, liquidity_0);
-
-            amount_0 =
-                get_amounts_0_from_liquidity(sqrt_price_current, sqrt_price_upper, liquidity);
-            amount_1 =
-                get_amounts_1_from_liquidity(sqrt_price_lower, sqrt_price_current, liquidity);
-        }
-        //validate liquidity
-        require!(liquidity > 0, LiquidityError::ZeroLiquidity);
+            (
+                get_amount_0_delta(sqrt_price_current, sqrt_price_upper, liquidity, true)?,
+                get_amount_1_delta(sqrt_price_lower, sqrt_price_current, liquidity, true)?,
+            )
+        };
+
+        //the vaults must receive the full amounts, the user pays transfer fees on top
+        let amount_0_transfer = amount_0
+            .checked_add(get_transfer_inverse_fee(&self.token_0_mint, amount_0)?)
+            .ok_or(error!(ClmmError::AmountOverflow))?;
+        let amount_1_transfer = amount_1
+            .checked_add(get_transfer_inverse_fee(&self.token_1_mint, amount_1)?)
+            .ok_or(error!(ClmmError::AmountOverflow))?;
+
+        //slippage check, the price may have moved since the quote
+        require!(
+            amount_0_transfer <= amount_0_max && amount_1_transfer <= amount_1_max,
+            ClmmError::PriceSlippageCheck
+        );
 
         //load the tick state from the tick array
+        let pool_key = self.pool_state_account.key();
         let tick_lower_state = self.tick_array_lower.load()?;
         let tick_upper_state = self.tick_array_upper.load()?;
-        let lower_start_index = tick_lower_state.start_tick_index;
-        let upper_start_index = tick_upper_state.start_tick_index;
+        tick_lower_state.validate(&self.tick_array_lower.key(), &pool_key)?;
+        tick_upper_state.validate(&self.tick_array_upper.key(), &pool_key)?;
 
         //calculate the index in the array
-        let tick_spacing = self.pool_state_account.tick_spacing as i32;
-        let lower_tick_index = (lower_position - lower_start_index) / tick_spacing;
-        let upper_tick_index = (upper_position - upper_start_index) / tick_spacing;
+        let tick_spacing = self.pool_state_account.tick_spacing;
+        let lower_tick_index = tick_lower_state.get_tick_offset(lower_position, tick_spacing)?;
+        let upper_tick_index = tick_upper_state.get_tick_offset(upper_position, tick_spacing)?;
 
         //get the tick states
-        let lower_tick = tick_lower_state.ticks[lower_tick_index as usize];
-        let upper_tick = tick_upper_state.ticks[upper_tick_index as usize];
-
-        //access the fee growth
-        let fee_growth_global = self.pool_state_account.fee_growth_global_0;
-
-        //calculat the fee growth inside for token_0
-        let fee_growth_below_0 = get_fee_growth_below(
-            current_tick,
-            lower_position,
-            fee_growth_global,
-            lower_tick.fee_growth_outside_0,
-        );
-
-        let fee_growth_above_0 = get_fee_growth_above(
-            current_tick,
-            upper_position,
-            fee_growth_global,
-            upper_tick.fee_growth_outside_0,
-        );
-
-        // Calculate fee_growth_inside for token0
-        let fee_growth_inside_0 = fee_growth_global - fee_growth_below_0 - fee_growth_above_0;
-
-        // Calculate fee_growth_inside for token1
-        let fee_growth_global_1 = self.pool_state_account.fee_growth_global_1;
-        let fee_growth_below_1 = get_fee_growth_below(
-            current_tick,
-            lower_position,
-            fee_growth_global_1,
-            lower_tick.fee_growth_outside_1,
-        );
-        let fee_growth_above_1 = get_fee_growth_above(
-            current_tick,
-            upper_position,
-            fee_growth_global_1,
-            upper_tick.fee_growth_outside_1,
-        );
-        let fee_growth_inside_1 = fee_growth_global_1 - fee_growth_below_1 - fee_growth_above_1;
-
-        // Calculate tokens owed using OLD liquidity
-        let q64: u128 = 1u128 << 64;
-        let tokens_owed_0 = ((fee_growth_inside_0
-            .wrapping_sub(self.position.fee_growth_inside_0_last))
-            * liquidity_current
-            / q64) as u64;
-        let tokens_owed_1 = ((fee_growth_inside_1
-            .wrapping_sub(self.position.fee_growth_inside_1_last))
-            * liquidity_current
-            / q64) as u64;
+        let lower_tick = tick_lower_state.ticks[lower_tick_index];
+        let upper_tick = tick_upper_state.ticks[upper_tick_index];
 
         // Drop immutable borrows before mutable operations
         drop(tick_lower_state);
         drop(tick_upper_state);
 
-        // Update position fee state
-        self.position.tokens_owed_0 += tokens_owed_0;
-        self.position.tokens_owed_1 += tokens_owed_1;
-        self.position.fee_growth_inside_0_last = fee_growth_inside_0;
-        self.position.fee_growth_inside_1_last = fee_growth_inside_1;
+        // Bring owed fees up to date using the old liquidity
+        update_position_fees(
+            &mut self.position,
+            &self.pool_state_account,
+            &lower_tick,
+            &upper_tick,
+        )?;
 
         // Transfer token0 from user to vault
         if amount_0 > 0 {
@@ -197,8 +161,9 @@ impl<'info> IncreaseLiquidity<'info> {
                 authority: self.signer.to_account_info(),
                 mint: self.token_0_mint.to_account_info(),
             };
-            let cpi_ctx_0 = CpiContext::new(self.token_program.to_account_info(), cpi_accounts_0);
-            token_interface::transfer_checked(cpi_ctx_0, amount_0, self.token_0_mint.decimals)?;
+            let cpi_ctx_0 = CpiContext::new(self.token_program.to_account_info(), cpi_accounts_0)
+                .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_0, amount_0_transfer, self.token_0_mint.decimals)?;
         }
 
         // Transfer token1 from user to vault
@@ -209,39 +174,92 @@ impl<'info> IncreaseLiquidity<'info> {
                 authority: self.signer.to_account_info(),
                 mint: self.token_1_mint.to_account_info(),
             };
-            let cpi_ctx_1 = CpiContext::new(self.token_program.to_account_info(), cpi_accounts_1);
-            token_interface::transfer_checked(cpi_ctx_1, amount_1, self.token_1_mint.decimals)?;
+            let cpi_ctx_1 = CpiContext::new(self.token_program.to_account_info(), cpi_accounts_1)
+                .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx_1, amount_1_transfer, self.token_1_mint.decimals)?;
         }
 
         // Update tick states
+        let liquidity_signed =
+            i128::try_from(liquidity).map_err(|_| error!(ClmmError::MathOverflow))?;
+        let reward_growths_global = self.pool_state_account.reward_growths_global();
         {
             let mut tick_array_lower_mut = self.tick_array_lower.load_mut()?;
-            let lower_tick_mut = &mut tick_array_lower_mut.ticks[lower_tick_index as usize];
-            lower_tick_mut.liquidity_gross += liquidity;
-            lower_tick_mut.liquidity_net += liquidity as i128;
+            let lower_tick_mut = &mut tick_array_lower_mut.ticks[lower_tick_index];
+            lower_tick_mut.liquidity_gross = lower_tick_mut
+                .liquidity_gross
+                .checked_add(liquidity)
+                .ok_or(error!(ClmmError::MathOverflow))?;
+            lower_tick_mut.liquidity_net = lower_tick_mut
+                .liquidity_net
+                .checked_add(liquidity_signed)
+                .ok_or(error!(ClmmError::MathOverflow))?;
             if lower_tick_mut.initialized == 0 {
                 lower_tick_mut.initialized = 1;
+                lower_tick_mut.init_reward_growths_outside(
+                    lower_position,
+                    current_tick,
+                    reward_growths_global,
+                );
             }
         }
 
         {
             let mut tick_array_upper_mut = self.tick_array_upper.load_mut()?;
-            let upper_tick_mut = &mut tick_array_upper_mut.ticks[upper_tick_index as usize];
-            upper_tick_mut.liquidity_gross += liquidity;
-            upper_tick_mut.liquidity_net -= liquidity as i128;
+            let upper_tick_mut = &mut tick_array_upper_mut.ticks[upper_tick_index];
+            upper_tick_mut.liquidity_gross = upper_tick_mut
+                .liquidity_gross
+                .checked_add(liquidity)
+                .ok_or(error!(ClmmError::MathOverflow))?;
+            upper_tick_mut.liquidity_net = upper_tick_mut
+                .liquidity_net
+                .checked_sub(liquidity_signed)
+                .ok_or(error!(ClmmError::MathUnderflow))?;
             if upper_tick_mut.initialized == 0 {
                 upper_tick_mut.initialized = 1;
+                upper_tick_mut.init_reward_growths_outside(
+                    upper_position,
+                    current_tick,
+                    reward_growths_global,
+                );
             }
         }
 
+        // Update position rewards, after new ticks took their reward checkpoint
+        {
+            let tick_array_lower = self.tick_array_lower.load()?;
+            let tick_array_upper = self.tick_array_upper.load()?;
+            update_position_rewards(
+                &mut self.position,
+                &self.pool_state_account,
+                &tick_array_lower.ticks[lower_tick_index],
+                &tick_array_upper.ticks[upper_tick_index],
+            )?;
+        }
+
         // Update position liquidity
-        self.position.liquidity += liquidity;
+        self.position.liquidity = self
+            .position
+            .liquidity
+            .checked_add(liquidity)
+            .ok_or(error!(ClmmError::MathOverflow))?;
 
         // Update pool liquidity if position is in range
         if lower_position <= current_tick && current_tick < upper_position {
-            self.pool_state_account.liquidity += liquidity;
+            self.pool_state_account.liquidity = self
+                .pool_state_account
+                .liquidity
+                .checked_add(liquidity)
+                .ok_or(error!(ClmmError::MathOverflow))?;
         }
 
-        Ok(())
+        Ok(LiquidityIncreased {
+            pool_state: self.pool_state_account.key(),
+            position: self.position.key(),
+            liquidity,
+            amount_0,
+            amount_1,
+            position_liquidity: self.position.liquidity,
+        })
     }
 }
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::events::ObservationCardinalityIncreased;
+use crate::states::{
+    oracle::{ObservationState, OBSERVATION_CAPACITY},
+    pool::LpPoolStateShape,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct IncreaseObservationCardinality<'info> {
+    //anyone may extend the oracle window
+    pub signer: Signer<'info>,
+
+    //pool state
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //price observations for the pool
+    #[account(mut, address = pool_state_account.observation_key @ ClmmError::AccountMismatch)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+impl<'info> IncreaseObservationCardinality<'info> {
+    pub fn handler(&mut self, cardinality_next: u16) -> Result<ObservationCardinalityIncreased> {
+        require!(
+            cardinality_next as usize <= OBSERVATION_CAPACITY,
+            ClmmError::InvalidObservationCardinality
+        );
+
+        //the buffer only grows, the new slots are used once it wraps
+        let mut observation_state = self.observation_state.load_mut()?;
+        let cardinality_next_old = observation_state.cardinality_next;
+        if cardinality_next > cardinality_next_old {
+            observation_state.cardinality_next = cardinality_next;
+        }
+
+        Ok(ObservationCardinalityIncreased {
+            pool_state: self.pool_state_account.key(),
+            cardinality_next_old,
+            cardinality_next_new: observation_state.cardinality_next,
+        })
+    }
+}
@@ -1,17 +1,33 @@
-use crate::states::pool::LpPoolStateShape;
+use crate::errors::ClmmError;
+use crate::events::PoolCreated;
+use crate::libraries::{
+    get_tick_at_sqrt_price, is_pool_order,
+    tick_math::{MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64},
+};
+use crate::states::{
+    config::AmmConfig,
+    oracle::{ObservationState, OBSERVATION_SEED},
+    pool::{LpPoolStateShape, POOL_SEED, POOL_VAULT_0_SEED, POOL_VAULT_1_SEED},
+};
+use crate::utils::check_mint_extensions;
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 //initialize struct
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     //signer of the pool
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    //fee tier the pool takes its parameters from
+    pub amm_config: Account<'info, AmmConfig>,
+
     /// Token 0 mint (e.g., USDC)
     pub token_0_mint: InterfaceAccount<'info, Mint>,
-    /// Token 1 mint (e.g., WSOL)  
+    /// Token 1 mint (e.g., WSOL), must sort after token 0
+    #[account(constraint = is_pool_order(&token_0_mint.key(), &token_1_mint.key()) @ ClmmError::MintsNotCanonical)]
     pub token_1_mint: InterfaceAccount<'info, Mint>,
 
     //token program
@@ -25,7 +41,12 @@ pub struct Initialize<'info> {
         init, 
         payer = signer, 
         space = 8 + LpPoolStateShape::INIT_SPACE, 
-        seeds = [b"pool_state_v1", token_0_mint.key().as_ref(), token_1_mint.key().as_ref()], 
+        seeds = [
+            POOL_SEED,
+            amm_config.key().as_ref(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref()
+        ],
         bump
     )]
     pub pool_state_account: Account<'info, LpPoolStateShape>,
@@ -37,7 +58,11 @@ pub struct Initialize<'info> {
         token::mint = token_0_mint, 
         token::authority = pool_state_account, 
         token::token_program = token_program, 
-        seeds = [b"token_0_vault", token_0_mint.key().as_ref()], 
+        seeds = [
+            POOL_VAULT_0_SEED,
+            pool_state_account.key().as_ref(),
+            token_0_mint.key().as_ref()
+        ],
         bump
     )]
     pub token_0_vault: InterfaceAccount<'info, TokenAccount>,
@@ -48,9 +73,87 @@ pub struct Initialize<'info> {
         token::mint = token_1_mint, 
         token::authority = pool_state_account, 
         token::token_program = token_program, 
-        seeds = [b"token_1_vault", token_1_mint.key().as_ref()], 
+        seeds = [
+            POOL_VAULT_1_SEED,
+            pool_state_account.key().as_ref(),
+            token_1_mint.key().as_ref()
+        ],
         bump
     )]
     pub token_1_vault: InterfaceAccount<'info, TokenAccount>,
+
+    //price observations for the pool
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + std::mem::size_of::<ObservationState>(),
+        seeds = [OBSERVATION_SEED, pool_state_account.key().as_ref()],
+        bump
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
 }
 
+impl<'info> Initialize<'info> {
+    pub fn handler(
+        &mut self,
+        initial_sqrt_price_x64: u128,
+        bumps: &InitializeBumps,
+    ) -> Result<PoolCreated> {
+        //validate the pool parameters
+        require!(
+            (MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64).contains(&initial_sqrt_price_x64),
+            ClmmError::InvalidSqrtPrice
+        );
+        let allowed_hook_programs = self.amm_config.transfer_hook_programs;
+        check_mint_extensions(&self.token_0_mint, &allowed_hook_programs)?;
+        check_mint_extensions(&self.token_1_mint, &allowed_hook_programs)?;
+
+        //store mints and vault addresses in pool state
+        let pool = &mut self.pool_state_account;
+        pool.token_0_mint = self.token_0_mint.key();
+        pool.token_1_mint = self.token_1_mint.key();
+        pool.token_0_vault_address = self.token_0_vault.key();
+        pool.token_1_vault_address = self.token_1_vault.key();
+        pool.bump = bumps.pool_state_account;
+
+        //configuration copied from the fee tier
+        pool.amm_config = self.amm_config.key();
+        pool.tick_spacing = self.amm_config.tick_spacing;
+
+        //starting price, no liquidity or fees yet
+        pool.sqrt_price_x64 = initial_sqrt_price_x64;
+        pool.tick_current = get_tick_at_sqrt_price(initial_sqrt_price_x64);
+        pool.liquidity = 0;
+        pool.fee_growth_global_0 = 0;
+        pool.fee_growth_global_1 = 0;
+        pool.protocol_fees_token_0 = 0;
+        pool.protocol_fees_token_1 = 0;
+
+        //no rewards until an admin opens a slot
+        let clock = Clock::get()?;
+        pool.reward_last_updated_timestamp = clock.unix_timestamp as u64;
+        pool.reward_infos = Default::default();
+
+        //first observation at the starting price
+        pool.observation_key = self.observation_state.key();
+        self.observation_state.load_init()?.initialize(
+            pool.key(),
+            bumps.observation_state,
+            clock.unix_timestamp as u64,
+            clock.slot,
+        );
+
+        Ok(PoolCreated {
+            pool_state: pool.key(),
+            amm_config: pool.amm_config,
+            token_0_mint: pool.token_0_mint,
+            token_1_mint: pool.token_1_mint,
+            token_0_vault: pool.token_0_vault_address,
+            token_1_vault: pool.token_1_vault_address,
+            tick_spacing: pool.tick_spacing,
+            fee_rate: self.amm_config.trade_fee_rate,
+            sqrt_price_x64: pool.sqrt_price_x64,
+            tick: pool.tick_current,
+        })
+    }
+}
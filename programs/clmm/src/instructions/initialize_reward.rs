@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::errors::ClmmError;
+use crate::events::RewardInitialized;
+use crate::states::{
+    config::AmmConfig,
+    pool::{LpPoolStateShape, POOL_REWARD_VAULT_SEED, REWARD_NUM},
+};
+use crate::utils::check_mint_extensions;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct InitializeReward<'info> {
+    //config admin
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    //fee tier the pool was created from
+    #[account(has_one = owner @ ClmmError::InvalidAuthority)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    //pool state
+    #[account(mut, has_one = amm_config @ ClmmError::AmmConfigMismatch)]
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //token emitted as reward, kept apart from the pool mints so lp payouts never touch reward funds
+    #[account(
+        constraint = reward_token_mint.key() != pool_state_account.token_0_mint
+            && reward_token_mint.key() != pool_state_account.token_1_mint
+            @ ClmmError::RewardMintIsPoolMint
+    )]
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    //vault the emissions are paid from, funded by the admin
+    #[account(
+        init,
+        payer = owner,
+        token::mint = reward_token_mint,
+        token::authority = pool_state_account,
+        token::token_program = token_program,
+        seeds = [
+            POOL_REWARD_VAULT_SEED,
+            pool_state_account.key().as_ref(),
+            reward_token_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //token program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    //system program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeReward<'info> {
+    pub fn handler(&mut self, reward_index: u8) -> Result<RewardInitialized> {
+        let pool = &mut self.pool_state_account;
+
+        //slots are filled in order
+        let reward_index = reward_index as usize;
+        require!(
+            reward_index < REWARD_NUM
+                && !pool.reward_infos[reward_index].initialized()
+                && pool.reward_infos[..reward_index]
+                    .iter()
+                    .all(|reward_info| reward_info.initialized()),
+            ClmmError::InvalidRewardIndex
+        );
+
+        //reward mints get the same extension allowlist as pool mints
+        check_mint_extensions(
+            &self.reward_token_mint,
+            &self.amm_config.transfer_hook_programs,
+        )?;
+
+        //bring other slots up to date, emissions start at zero
+        pool.update_reward_infos(Clock::get()?.unix_timestamp as u64)?;
+        let reward_info = &mut pool.reward_infos[reward_index];
+        reward_info.mint = self.reward_token_mint.key();
+        reward_info.vault = self.reward_vault.key();
+        reward_info.emissions_per_second_x64 = 0;
+        reward_info.growth_global_x64 = 0;
+
+        Ok(RewardInitialized {
+            pool_state: self.pool_state_account.key(),
+            reward_index: reward_index as u8,
+            reward_mint: self.reward_token_mint.key(),
+            reward_vault: self.reward_vault.key(),
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::events::TickArrayInitialized;
+use crate::states::{
+    pool::LpPoolStateShape,
+    tick::{TickArrayState, TICK_ARRAY_SEED},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(start_tick_index: i32)]
+pub struct InitializeTickArray<'info> {
+    //payer for the tick array rent
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //pool the array belongs to
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //tick array
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + std::mem::size_of::<TickArrayState>(),
+        seeds = [
+            TICK_ARRAY_SEED,
+            pool_state_account.key().as_ref(),
+            &start_tick_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+
+    //system program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeTickArray<'info> {
+    pub fn handler(
+        &mut self,
+        start_tick_index: i32,
+        bumps: &InitializeTickArrayBumps,
+    ) -> Result<TickArrayInitialized> {
+        //start index must line up with the pool's array width
+        require!(
+            TickArrayState::is_valid_start_index(
+                start_tick_index,
+                self.pool_state_account.tick_spacing
+            ),
+            ClmmError::InvalidStartIndex
+        );
+
+        let mut tick_array = self.tick_array.load_init()?;
+        tick_array.pool_id = self.pool_state_account.key();
+        tick_array.start_tick_index = start_tick_index;
+        tick_array.bump = bumps.tick_array;
+
+        Ok(TickArrayInitialized {
+            pool_state: self.pool_state_account.key(),
+            tick_array: self.tick_array.key(),
+            start_tick_index,
+        })
+    }
+}
@@ -1,12 +1,40 @@
+pub mod close_position;
+pub mod collect_fees;
+pub mod collect_protocol_fees;
+pub mod collect_reward;
+pub mod create_amm_config;
 pub mod decrease_liquidity;
 pub mod increase_liquidity;
+pub mod increase_observation_cardinality;
 pub mod initialize_pool;
+pub mod initialize_reward;
+pub mod initialize_tick_array;
+pub mod observe;
 pub mod open_position;
+pub mod open_position_with_nft;
+pub mod position_delegate;
+pub mod set_reward_emissions;
 pub mod swap;
+pub mod update_amm_config;
+pub mod update_transfer_hook_allowlist;
 
-// Re-export account structs for use in lib.rs
-pub use decrease_liquidity::DecreaseLiquidity;
-pub use increase_liquidity::IncreaseLiquidity;
-pub use initialize_pool::Initialize;
-pub use open_position::OpenPosition;
-pub use swap::Swap;
+// Re-export account structs (and anchor's generated client modules) for lib.rs
+pub use close_position::*;
+pub use collect_fees::*;
+pub use collect_protocol_fees::*;
+pub use collect_reward::*;
+pub use create_amm_config::*;
+pub use decrease_liquidity::*;
+pub use increase_liquidity::*;
+pub use increase_observation_cardinality::*;
+pub use initialize_pool::*;
+pub use initialize_reward::*;
+pub use initialize_tick_array::*;
+pub use observe::*;
+pub use open_position::*;
+pub use open_position_with_nft::*;
+pub use position_delegate::*;
+pub use set_reward_emissions::*;
+pub use swap::*;
+pub use update_amm_config::*;
+pub use update_transfer_hook_allowlist::*;
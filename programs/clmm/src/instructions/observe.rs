@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::states::{oracle::ObservationState, pool::LpPoolStateShape};
+
+//cumulatives for each requested offset, in request order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ObserveResult {
+    pub tick_cumulatives: Vec<i64>,
+    pub seconds_per_liquidity_cumulatives_x64: Vec<u128>,
+}
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    //pool state
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //price observations for the pool
+    #[account(address = pool_state_account.observation_key @ ClmmError::AccountMismatch)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+impl<'info> Observe<'info> {
+    pub fn handler(&self, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        let pool = &self.pool_state_account;
+        let observation_state = self.observation_state.load()?;
+
+        let mut result = ObserveResult {
+            tick_cumulatives: Vec::with_capacity(seconds_agos.len()),
+            seconds_per_liquidity_cumulatives_x64: Vec::with_capacity(seconds_agos.len()),
+        };
+        for seconds_ago in seconds_agos {
+            let (tick_cumulative, seconds_per_liquidity_cumulative_x64) = observation_state
+                .observe_single(now, seconds_ago, pool.tick_current, pool.liquidity)?;
+            result.tick_cumulatives.push(tick_cumulative);
+            result
+                .seconds_per_liquidity_cumulatives_x64
+                .push(seconds_per_liquidity_cumulative_x64);
+        }
+        Ok(result)
+    }
+}
@@ -1,14 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self},
-    token_interface::{self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
-};
 
+use crate::events::PositionOpened;
+use crate::libraries::check_ticks_range;
 use crate::states::pool::LpPoolStateShape;
-use crate::states::Position;
+use crate::states::{Position, POSITION_SEED};
 
 //files
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(tick_lower: i32, tick_upper: i32)]
 pub struct OpenPosition<'info> {
@@ -23,12 +21,14 @@ pub struct OpenPosition<'info> {
     pub pool_state_account: Account<'info, LpPoolStateShape>,
 
     //postion
-    #[account(init, payer= signer, space= 8+Position::INIT_SPACE, seeds = [b"position", signer.key().as_ref(), pool_state_account.key().as_ref(),&tick_upper.to_le_bytes(), &tick_lower.to_le_bytes()], bump)]
+    #[account(init, payer= signer, space= 8+Position::INIT_SPACE, seeds = [POSITION_SEED, signer.key().as_ref(), pool_state_account.key().as_ref(),&tick_upper.to_le_bytes(), &tick_lower.to_le_bytes()], bump)]
     pub position: Account<'info, Position>,
 }
 
 impl<'info> OpenPosition<'info> {
-    pub fn handler(&mut self, tick_lower: i32, tick_upper: i32) -> Result<()> {
+    pub fn handler(&mut self, tick_lower: i32, tick_upper: i32) -> Result<PositionOpened> {
+        check_ticks_range(tick_lower, tick_upper, self.pool_state_account.tick_spacing)?;
+
         let position = &mut self.position;
         position.pool_id = self.pool_state_account.key();
         position.owner = self.signer.key();
@@ -39,6 +39,18 @@ impl<'info> OpenPosition<'info> {
         position.tokens_owed_1 = 0;
         position.fee_growth_inside_0_last = 0;
         position.fee_growth_inside_1_last = 0;
-        Ok(())
+        position.nft_mint = Pubkey::default();
+        position.delegate = Pubkey::default();
+        position.delegate_permissions = 0;
+        position.delegate_owner = Pubkey::default();
+        position.reward_infos = Default::default();
+        Ok(PositionOpened {
+            pool_state: self.pool_state_account.key(),
+            position: self.position.key(),
+            owner: self.position.owner,
+            nft_mint: self.position.nft_mint,
+            tick_lower,
+            tick_upper,
+        })
     }
 }
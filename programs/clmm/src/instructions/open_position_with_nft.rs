@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        self, spl_token_2022::instruction::AuthorityType, Mint, MintTo, SetAuthority, TokenAccount,
+        TokenInterface,
+    },
+};
+
+use crate::events::PositionOpened;
+use crate::libraries::check_ticks_range;
+use crate::states::pool::LpPoolStateShape;
+use crate::states::{Position, POSITION_SEED};
+
+//position represented by a one-of-one token, ownership follows the token
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenPositionWithNft<'info> {
+    //signer receiving the position nft
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //pool static
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+
+    //fresh mint for the position nft, frozen at a supply of one after minting
+    #[account(init, payer = signer, mint::decimals = 0,
+    mint::authority = pool_state_account, mint::token_program = token_program)]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    //signer's token account holding the nft
+    #[account(init, payer = signer, associated_token::mint = position_nft_mint,
+    associated_token::authority = signer, associated_token::token_program = token_program)]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //postion, keyed by the nft mint so it survives transfers
+    #[account(init, payer = signer, space = 8 + Position::INIT_SPACE,
+    seeds = [POSITION_SEED, position_nft_mint.key().as_ref()], bump)]
+    pub position: Account<'info, Position>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenPositionWithNft<'info> {
+    pub fn handler(&mut self, tick_lower: i32, tick_upper: i32) -> Result<PositionOpened> {
+        check_ticks_range(tick_lower, tick_upper, self.pool_state_account.tick_spacing)?;
+
+        let position = &mut self.position;
+        position.pool_id = self.pool_state_account.key();
+        position.owner = self.signer.key();
+        position.tick_lower = tick_lower;
+        position.liquidity = 0;
+        position.tick_upper = tick_upper;
+        position.tokens_owed_0 = 0;
+        position.tokens_owed_1 = 0;
+        position.fee_growth_inside_0_last = 0;
+        position.fee_growth_inside_1_last = 0;
+        position.nft_mint = self.position_nft_mint.key();
+        position.delegate = Pubkey::default();
+        position.delegate_permissions = 0;
+        position.delegate_owner = Pubkey::default();
+        position.reward_infos = Default::default();
+
+        //pool pda is the mint authority
+        let seeds = self.pool_state_account.seeds();
+        let signer_seeds = &[&seeds[..]];
+
+        //mint the single position token to the signer
+        let cpi_accounts_mint = MintTo {
+            mint: self.position_nft_mint.to_account_info(),
+            to: self.position_nft_account.to_account_info(),
+            authority: self.pool_state_account.to_account_info(),
+        };
+        let cpi_ctx_mint = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts_mint,
+            signer_seeds,
+        );
+        token_interface::mint_to(cpi_ctx_mint, 1)?;
+
+        //drop the mint authority so no second token can ever exist
+        let cpi_accounts_authority = SetAuthority {
+            current_authority: self.pool_state_account.to_account_info(),
+            account_or_mint: self.position_nft_mint.to_account_info(),
+        };
+        let cpi_ctx_authority = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts_authority,
+            signer_seeds,
+        );
+        token_interface::set_authority(cpi_ctx_authority, AuthorityType::MintTokens, None)?;
+
+        Ok(PositionOpened {
+            pool_state: self.pool_state_account.key(),
+            position: self.position.key(),
+            owner: self.position.owner,
+            nft_mint: self.position.nft_mint,
+            tick_lower,
+            tick_upper,
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    errors::ClmmError,
+    events::DelegateUpdated,
+    states::{Position, PERMISSION_ALL},
+};
+
+//owner-only management of the position delegate
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePositionDelegate<'info> {
+    //position owner
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+
+    //token account holding the position nft, only for nft positions
+    pub position_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+}
+
+impl<'info> UpdatePositionDelegate<'info> {
+    pub fn set_delegate(&mut self, delegate: Pubkey, permissions: u8) -> Result<DelegateUpdated> {
+        let owner = self.check_owner()?;
+        require!(
+            permissions != 0 && permissions & !PERMISSION_ALL == 0,
+            ClmmError::InvalidDelegatePermissions
+        );
+
+        self.position.delegate = delegate;
+        self.position.delegate_permissions = permissions;
+        self.position.delegate_owner = owner;
+        Ok(self.delegate_updated())
+    }
+
+    pub fn revoke_delegate(&mut self) -> Result<DelegateUpdated> {
+        self.check_owner()?;
+
+        self.position.delegate = Pubkey::default();
+        self.position.delegate_permissions = 0;
+        self.position.delegate_owner = Pubkey::default();
+        Ok(self.delegate_updated())
+    }
+
+    fn delegate_updated(&self) -> DelegateUpdated {
+        DelegateUpdated {
+            position: self.position.key(),
+            delegate: self.position.delegate,
+            permissions: self.position.delegate_permissions,
+        }
+    }
+
+    fn check_owner(&self) -> Result<Pubkey> {
+        let owner = self
+            .position
+            .get_owner(self.position_nft_account.as_deref())?;
+        require_keys_eq!(owner, self.signer.key(), ClmmError::NotPositionOwner);
+        Ok(owner)
+    }
+}
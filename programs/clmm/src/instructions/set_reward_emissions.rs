@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::events::RewardEmissionsUpdated;
+use crate::states::{
+    config::AmmConfig,
+    pool::{LpPoolStateShape, REWARD_NUM},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetRewardEmissions<'info> {
+    //config admin
+    pub owner: Signer<'info>,
+
+    //fee tier the pool was created from
+    #[account(has_one = owner @ ClmmError::InvalidAuthority)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    //pool state
+    #[account(mut, has_one = amm_config @ ClmmError::AmmConfigMismatch)]
+    pub pool_state_account: Account<'info, LpPoolStateShape>,
+}
+
+impl<'info> SetRewardEmissions<'info> {
+    pub fn handler(
+        &mut self,
+        reward_index: u8,
+        emissions_per_second_x64: u128,
+    ) -> Result<RewardEmissionsUpdated> {
+        let pool = &mut self.pool_state_account;
+        let reward_index = reward_index as usize;
+        require!(reward_index < REWARD_NUM, ClmmError::InvalidRewardIndex);
+        require!(
+            pool.reward_infos[reward_index].initialized(),
+            ClmmError::RewardNotInitialized
+        );
+
+        //growth so far accrues at the old rate
+        pool.update_reward_infos(Clock::get()?.unix_timestamp as u64)?;
+        pool.reward_infos[reward_index].emissions_per_second_x64 = emissions_per_second_x64;
+
+        Ok(RewardEmissionsUpdated {
+            pool_state: pool.key(),
+            reward_index: reward_index as u8,
+            emissions_per_second_x64,
+        })
+    }
+}
@@ -1,30 +1,47 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
-
-use crate::errors::SwapError;
-use crate::libraries::swap_math::{
-    compute_swap_step, find_next_initialized_tick, get_amount_0_delta, get_amount_1_delta, Q64,
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::ClmmError;
+use crate::events::SwapEvent;
+use crate::states::{
+    config::AmmConfig,
+    oracle::ObservationState,
+    pool::LpPoolStateShape,
+    swapstate::{to_i64, SwapParams, SwapState},
+    tick::TickArrayState,
 };
-use crate::libraries::tick_math::{get_sqrt_price_at_tick, get_tick_at_sqrt_price};
-use crate::states::{pool::LpPoolStateShape, tick::TickArrayState};
+use crate::utils::{get_transfer_fee, get_transfer_inverse_fee, transfer_checked_with_hook};
 
 pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
 pub const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Swap<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    #[account(mut)]
+    //fee tier the pool was created from, its current rates apply to this swap
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(mut, has_one = amm_config @ ClmmError::AmmConfigMismatch)]
     pub pool_state: Account<'info, LpPoolStateShape>,
 
     pub token_program: Interface<'info, TokenInterface>,
 
     /// The mint of input token
+    #[account(
+        constraint = input_mint.key() == pool_state.token_0_mint
+            || input_mint.key() == pool_state.token_1_mint @ ClmmError::AccountMismatch
+    )]
     pub input_mint: InterfaceAccount<'info, Mint>,
 
-    /// The mint of output token
+    /// The mint of output token, the other pool mint
+    #[account(
+        constraint = output_mint.key() != input_mint.key()
+            && (output_mint.key() == pool_state.token_0_mint
+                || output_mint.key() == pool_state.token_1_mint) @ ClmmError::AccountMismatch
+    )]
     pub output_mint: InterfaceAccount<'info, Mint>,
 
     /// User's input token account
@@ -36,35 +53,32 @@ pub struct Swap<'info> {
     pub user_output_account: InterfaceAccount<'info, TokenAccount>,
 
     /// Pool's input vault
-    #[account(mut, token::authority = pool_state)]
+    #[account(mut, address = pool_state.vault_address(&input_mint.key()) @ ClmmError::AccountMismatch)]
     pub input_vault: InterfaceAccount<'info, TokenAccount>,
 
     /// Pool's output vault
-    #[account(mut, token::authority = pool_state)]
+    #[account(mut, address = pool_state.vault_address(&output_mint.key()) @ ClmmError::AccountMismatch)]
     pub output_vault: InterfaceAccount<'info, TokenAccount>,
 
     /// Tick array containing current tick
+    /// Further arrays in the swap direction are passed, in order, as remaining accounts
     #[account(mut)]
     pub tick_array: AccountLoader<'info, TickArrayState>,
-}
 
-/// State during swap execution
-struct SwapState {
-    amount_remaining: u64,
-    amount_calculated: u64,
-    sqrt_price_x64: u128,
-    tick: i32,
-    liquidity: u128,
-    fee_growth_global: u128,
+    /// Price observations, written once per slot
+    #[account(mut, address = pool_state.observation_key @ ClmmError::AccountMismatch)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
 }
 
 impl<'info> Swap<'info> {
     pub fn swap(
         &mut self,
-        amount_in: u64,
-        minimum_amount_out: u64,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        amount: u64,
+        other_amount_threshold: u64,
         sqrt_price_limit_x64: u128,
-    ) -> Result<()> {
+        is_base_input: bool,
+    ) -> Result<SwapEvent> {
         let pool = &mut self.pool_state;
 
         // Determine swap direction based on input mint
@@ -75,22 +89,53 @@ impl<'info> Swap<'info> {
             require!(
                 sqrt_price_limit_x64 < pool.sqrt_price_x64
                     && sqrt_price_limit_x64 >= MIN_SQRT_PRICE_X64,
-                SwapError::InvalidSqrtPriceLimit
+                ClmmError::InvalidSqrtPriceLimit
             );
         } else {
             require!(
                 sqrt_price_limit_x64 > pool.sqrt_price_x64
                     && sqrt_price_limit_x64 <= MAX_SQRT_PRICE_X64,
-                SwapError::InvalidSqrtPriceLimit
+                ClmmError::InvalidSqrtPriceLimit
             );
         }
 
-        require!(pool.liquidity > 0, SwapError::NoLiquidity);
-        require!(amount_in > 0, SwapError::ZeroAmount);
+        require!(pool.liquidity > 0, ClmmError::NoLiquidity);
+        require!(amount > 0, ClmmError::ZeroAmount);
+
+        // The pool works on vault-side amounts: exact input arrives net of the
+        // input mint's transfer fee, exact output leaves gross of the output mint's
+        let amount_specified = if is_base_input {
+            amount
+                .checked_sub(get_transfer_fee(&self.input_mint, amount)?)
+                .ok_or(error!(ClmmError::MathUnderflow))?
+        } else {
+            amount
+                .checked_add(get_transfer_inverse_fee(&self.output_mint, amount)?)
+                .ok_or(error!(ClmmError::AmountTooLarge))?
+        };
+        require!(amount_specified > 0, ClmmError::ZeroAmount);
+        let amount_specified_signed = to_i64(amount_specified)?;
+
+        // Record the pre-swap price for the oracle
+        let clock = Clock::get()?;
+        self.observation_state.load_mut()?.write(
+            clock.unix_timestamp as u64,
+            clock.slot,
+            pool.tick_current,
+            pool.liquidity,
+        );
+
+        // Accrue rewards up to now, they stay fixed while ticks are crossed
+        pool.update_reward_infos(clock.unix_timestamp as u64)?;
+        let reward_growths_global = pool.reward_growths_global();
 
         // Initialize swap state
         let mut state = SwapState {
-            amount_remaining: amount_in,
+            amount_specified_remaining: if is_base_input {
+                amount_specified_signed
+            } else {
+                -amount_specified_signed
+            },
             amount_calculated: 0,
             sqrt_price_x64: pool.sqrt_price_x64,
             tick: pool.tick_current,
@@ -100,123 +145,119 @@ impl<'info> Swap<'info> {
             } else {
                 pool.fee_growth_global_1
             },
+            fee_amount: 0,
+            protocol_fee: 0,
         };
-
-        // Load tick array
-        let mut tick_array_state = self.tick_array.load_mut()?;
-        let tick_spacing = pool.tick_spacing as i32;
-
-        // Main swap loop
-        while state.amount_remaining > 0 && state.sqrt_price_x64 != sqrt_price_limit_x64 {
-            // Find next initialized tick
-            let next_tick_result = find_next_initialized_tick(
-                &tick_array_state.ticks,
-                tick_array_state.start_tick_index,
-                state.tick,
-                tick_spacing,
-                zero_for_one,
-            );
-
-            let (next_tick, tick_index_in_array, is_initialized) = match next_tick_result {
-                Some(result) => result,
-                None => break,
-            };
-
-            // Get sqrt_price at next tick
-            let sqrt_price_next_tick = get_sqrt_price_at_tick(next_tick);
-
-            // Determine target price (capped by limit)
-            let sqrt_price_target = if zero_for_one {
-                sqrt_price_next_tick.max(sqrt_price_limit_x64)
+        let params = SwapParams {
+            zero_for_one,
+            is_base_input,
+            sqrt_price_limit_x64,
+            tick_spacing: pool.tick_spacing,
+            fee_rate: self.amm_config.trade_fee_rate,
+            protocol_fee_rate: self.amm_config.protocol_fee_rate,
+            fee_growth_global_other: if zero_for_one {
+                pool.fee_growth_global_1
             } else {
-                sqrt_price_next_tick.min(sqrt_price_limit_x64)
-            };
-
-            // Compute swap step
-            let (sqrt_price_next, amount_in_step, amount_out_step, fee_amount) = compute_swap_step(
-                state.sqrt_price_x64,
-                sqrt_price_target,
-                state.liquidity,
-                state.amount_remaining,
-                pool.fee_rate,
-                zero_for_one,
-            );
+                pool.fee_growth_global_0
+            },
+            reward_growths_global,
+        };
 
-            // Update amounts
-            state.amount_remaining = state
-                .amount_remaining
-                .saturating_sub(amount_in_step + fee_amount);
-            state.amount_calculated += amount_out_step;
-
-            // Update fee growth
-            if state.liquidity > 0 && fee_amount > 0 {
-                let fee_growth_delta = (fee_amount as u128 * Q64) / state.liquidity;
-                state.fee_growth_global += fee_growth_delta;
-            }
-
-            // Update price
-            state.sqrt_price_x64 = sqrt_price_next;
-
-            // Handle tick crossing
-            if sqrt_price_next == sqrt_price_next_tick && is_initialized {
-                let tick_state = &mut tick_array_state.ticks[tick_index_in_array];
-
-                // Flip fee growth outside
-                tick_state.fee_growth_outside_0 =
-                    pool.fee_growth_global_0 - tick_state.fee_growth_outside_0;
-                tick_state.fee_growth_outside_1 =
-                    pool.fee_growth_global_1 - tick_state.fee_growth_outside_1;
-
-                // Update liquidity
-                let liquidity_net = tick_state.liquidity_net;
-                if zero_for_one {
-                    // Going left, subtract liquidity_net
-                    if liquidity_net > 0 {
-                        state.liquidity = state.liquidity.saturating_sub(liquidity_net as u128);
-                    } else {
-                        state.liquidity = state.liquidity + ((-liquidity_net) as u128);
-                    }
-                } else {
-                    // Going right, add liquidity_net
-                    if liquidity_net > 0 {
-                        state.liquidity = state.liquidity + (liquidity_net as u128);
-                    } else {
-                        state.liquidity = state.liquidity.saturating_sub((-liquidity_net) as u128);
-                    }
-                }
-
-                // Update tick
-                state.tick = if zero_for_one {
-                    next_tick - 1
-                } else {
-                    next_tick
-                };
-            } else {
-                // Price didn't reach tick, calculate new tick from price
-                state.tick = get_tick_at_sqrt_price(state.sqrt_price_x64);
-            }
+        // Collect tick arrays in swap order: the one holding the current tick first
+        let pool_key = pool.key();
+        // Remaining accounts hold further tick arrays, then any transfer hook accounts
+        let tick_array_count = remaining_accounts
+            .iter()
+            .take_while(|account_info| is_tick_array(account_info))
+            .count();
+        let (tick_array_accounts, hook_accounts) = remaining_accounts.split_at(tick_array_count);
+        let mut tick_arrays = vec![self.tick_array.clone()];
+        for account_info in tick_array_accounts {
+            tick_arrays.push(AccountLoader::<TickArrayState>::try_from(account_info)?);
         }
 
-        drop(tick_array_state);
+        // Load tick array
+        let tick_array_state = tick_arrays[0].load_mut()?;
+        tick_array_state.validate(&tick_arrays[0].key(), &pool_key)?;
+        tick_array_state.get_tick_offset(pool.tick_current, pool.tick_spacing)?;
+
+        // Main swap loop, further arrays are validated as they are reached
+        let tick_arrays = &tick_arrays;
+        state.swap_across_tick_arrays(&params, tick_array_state, |array_index| {
+            let tick_array = tick_arrays
+                .get(array_index)
+                .ok_or(error!(ClmmError::NotEnoughTickArrays))?;
+            let tick_array_state = tick_array.load_mut()?;
+            tick_array_state.validate(&tick_array.key(), &pool_key)?;
+            Ok(tick_array_state)
+        })?;
 
         // Calculate final amounts
-        let amount_in_used = amount_in - state.amount_remaining;
-        let amount_out = state.amount_calculated;
+        let amount_specified_used = amount_specified
+            .checked_sub(state.amount_specified_remaining.unsigned_abs())
+            .ok_or(error!(ClmmError::MathUnderflow))?;
+        let (amount_in_used, amount_out) = if is_base_input {
+            (amount_specified_used, state.amount_calculated)
+        } else {
+            (state.amount_calculated, amount_specified_used)
+        };
 
-        // Slippage check
-        require!(
-            amount_out >= minimum_amount_out,
-            SwapError::TooLittleOutputReceived
-        );
+        // What the user actually pays and receives once transfer fees are applied
+        let amount_in_transfer = if is_base_input && state.amount_specified_remaining == 0 {
+            amount
+        } else {
+            amount_in_used
+                .checked_add(get_transfer_inverse_fee(&self.input_mint, amount_in_used)?)
+                .ok_or(error!(ClmmError::AmountTooLarge))?
+        };
+        let amount_out_received = amount_out
+            .checked_sub(get_transfer_fee(&self.output_mint, amount_out)?)
+            .ok_or(error!(ClmmError::MathUnderflow))?;
+
+        // Slippage check on the net figures
+        if is_base_input {
+            require!(
+                amount_out_received >= other_amount_threshold,
+                ClmmError::TooLittleOutputReceived
+            );
+        } else {
+            require!(
+                amount_in_transfer <= other_amount_threshold,
+                ClmmError::TooMuchInputPaid
+            );
+        }
 
-        // Update pool state
+        // Update pool state, keeping the pre-swap figures for the event
+        let swap_event = SwapEvent {
+            pool_state: pool_key,
+            sender: self.signer.key(),
+            zero_for_one,
+            amount_in: amount_in_used,
+            amount_out,
+            sqrt_price_x64_before: pool.sqrt_price_x64,
+            sqrt_price_x64_after: state.sqrt_price_x64,
+            tick_before: pool.tick_current,
+            tick_after: state.tick,
+            liquidity_before: pool.liquidity,
+            liquidity_after: state.liquidity,
+            fee_amount: state.fee_amount,
+            protocol_fee: state.protocol_fee,
+        };
         pool.sqrt_price_x64 = state.sqrt_price_x64;
         pool.tick_current = state.tick;
         pool.liquidity = state.liquidity;
         if zero_for_one {
             pool.fee_growth_global_0 = state.fee_growth_global;
+            pool.protocol_fees_token_0 = pool
+                .protocol_fees_token_0
+                .checked_add(state.protocol_fee)
+                .ok_or(error!(ClmmError::AmountOverflow))?;
         } else {
             pool.fee_growth_global_1 = state.fee_growth_global;
+            pool.protocol_fees_token_1 = pool
+                .protocol_fees_token_1
+                .checked_add(state.protocol_fee)
+                .ok_or(error!(ClmmError::AmountOverflow))?;
         }
 
         // Transfer input tokens: User -> Vault
@@ -226,20 +267,13 @@ impl<'info> Swap<'info> {
             authority: self.signer.to_account_info(),
             mint: self.input_mint.to_account_info(),
         };
-        let cpi_ctx_in = CpiContext::new(self.token_program.to_account_info(), cpi_accounts_in);
-        token_interface::transfer_checked(cpi_ctx_in, amount_in_used, self.input_mint.decimals)?;
+        let cpi_ctx_in = CpiContext::new(self.token_program.to_account_info(), cpi_accounts_in)
+            .with_remaining_accounts(hook_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx_in, amount_in_transfer, self.input_mint.decimals)?;
 
         // Transfer output tokens: Vault -> User (PDA signer)
-        let pool_bump = self.pool_state.bump;
-        let token_0_key = self.pool_state.token_0_mint;
-        let token_1_key = self.pool_state.token_1_mint;
-        let seeds: &[&[u8]] = &[
-            b"pool_state_v1",
-            token_0_key.as_ref(),
-            token_1_key.as_ref(),
-            &[pool_bump],
-        ];
-        let signer_seeds = &[seeds];
+        let seeds = self.pool_state.seeds();
+        let signer_seeds = &[&seeds[..]];
 
         let cpi_accounts_out = TransferChecked {
             from: self.output_vault.to_account_info(),
@@ -251,9 +285,17 @@ impl<'info> Swap<'info> {
             self.token_program.to_account_info(),
             cpi_accounts_out,
             signer_seeds,
-        );
-        token_interface::transfer_checked(cpi_ctx_out, amount_out, self.output_mint.decimals)?;
+        )
+        .with_remaining_accounts(hook_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx_out, amount_out, self.output_mint.decimals)?;
 
-        Ok(())
+        Ok(swap_event)
     }
 }
+
+fn is_tick_array(account_info: &AccountInfo) -> bool {
+    account_info.owner == &crate::ID
+        && account_info
+            .try_borrow_data()
+            .is_ok_and(|data| data.starts_with(TickArrayState::DISCRIMINATOR))
+}
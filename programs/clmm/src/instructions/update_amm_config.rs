@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::events::AmmConfigUpdated;
+use crate::libraries::FEE_RATE_DENOMINATOR;
+use crate::states::config::AmmConfig;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateAmmConfig<'info> {
+    //config admin
+    pub owner: Signer<'info>,
+
+    //fee tier, tick spacing is fixed once created
+    #[account(mut, has_one = owner @ ClmmError::InvalidAuthority)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+impl<'info> UpdateAmmConfig<'info> {
+    pub fn handler(
+        &mut self,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+    ) -> Result<AmmConfigUpdated> {
+        require!(
+            trade_fee_rate < FEE_RATE_DENOMINATOR,
+            ClmmError::InvalidFeeRate
+        );
+        require!(
+            protocol_fee_rate <= FEE_RATE_DENOMINATOR,
+            ClmmError::InvalidProtocolFeeRate
+        );
+
+        let amm_config = &mut self.amm_config;
+        amm_config.trade_fee_rate = trade_fee_rate;
+        amm_config.protocol_fee_rate = protocol_fee_rate;
+
+        Ok(AmmConfigUpdated {
+            amm_config: amm_config.key(),
+            trade_fee_rate,
+            protocol_fee_rate,
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::events::TransferHookAllowlistUpdated;
+use crate::states::config::{AmmConfig, MAX_TRANSFER_HOOK_PROGRAMS};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTransferHookAllowlist<'info> {
+    //config admin
+    pub owner: Signer<'info>,
+
+    //fee tier
+    #[account(mut, has_one = owner @ ClmmError::InvalidAuthority)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+impl<'info> UpdateTransferHookAllowlist<'info> {
+    pub fn handler(&mut self, hook_programs: Vec<Pubkey>) -> Result<TransferHookAllowlistUpdated> {
+        require!(
+            hook_programs.len() <= MAX_TRANSFER_HOOK_PROGRAMS,
+            ClmmError::TransferHookAllowlistFull
+        );
+
+        //replaces the whole list, existing pools are not re-checked
+        let mut transfer_hook_programs = [Pubkey::default(); MAX_TRANSFER_HOOK_PROGRAMS];
+        transfer_hook_programs[..hook_programs.len()].copy_from_slice(&hook_programs);
+        self.amm_config.transfer_hook_programs = transfer_hook_programs;
+
+        Ok(TransferHookAllowlistUpdated {
+            amm_config: self.amm_config.key(),
+            transfer_hook_programs,
+        })
+    }
+}
@@ -1,29 +1,76 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-// Keep modules for helper functions and logic
 pub mod errors;
+pub mod events;
+pub mod instructions;
 pub mod libraries;
 pub mod states;
+pub mod utils;
 
-// Re-export states for use in accounts
-use states::pool::LpPoolStateShape;
-use states::position::Position;
-use states::tick::TickArrayState;
+pub use instructions::*;
 
 declare_id!("CicZMzrBxTazWhSXGKXkkbnRiYFXSXm2Pe47RvW1X3qt");
 
+// protocol admin allowed to create fee tiers
+pub mod admin {
+    use anchor_lang::prelude::declare_id;
+    declare_id!("6QjxFc5VWhj6AKFTfMzevd8ZUnD8Fh16HVY8WnbUAqgw");
+}
+
 #[program]
 pub mod clmm {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    pub fn create_amm_config(
+        ctx: Context<CreateAmmConfig>,
+        index: u16,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(
+            index,
+            tick_spacing,
+            trade_fee_rate,
+            protocol_fee_rate,
+            &ctx.bumps,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn update_amm_config(
+        ctx: Context<UpdateAmmConfig>,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(trade_fee_rate, protocol_fee_rate)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn update_transfer_hook_allowlist(
+        ctx: Context<UpdateTransferHookAllowlist>,
+        hook_programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(hook_programs)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn initialize(ctx: Context<Initialize>, initial_sqrt_price_x64: u128) -> Result<()> {
         msg!("Initializing CLMM pool");
-        // Store mints and vault addresses in pool state
-        ctx.accounts.pool_state_account.token_0_mint = ctx.accounts.token_0_mint.key();
-        ctx.accounts.pool_state_account.token_1_mint = ctx.accounts.token_1_mint.key();
-        ctx.accounts.pool_state_account.token_0_vault_address = ctx.accounts.token_0_vault.key();
-        ctx.accounts.pool_state_account.token_1_vault_address = ctx.accounts.token_1_vault.key();
+        let event = ctx.accounts.handler(initial_sqrt_price_x64, &ctx.bumps)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn initialize_tick_array(
+        ctx: Context<InitializeTickArray>,
+        start_tick_index: i32,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(start_tick_index, &ctx.bumps)?;
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -32,81 +79,174 @@ pub mod clmm {
         tick_lower: i32,
         tick_upper: i32,
     ) -> Result<()> {
-        let position = &mut ctx.accounts.position;
-        position.pool_id = ctx.accounts.pool_state_account.key();
-        position.owner = ctx.accounts.signer.key();
-        position.tick_lower = tick_lower;
-        position.tick_upper = tick_upper;
-        position.liquidity = 0;
-        position.tokens_owed_0 = 0;
-        position.tokens_owed_1 = 0;
-        position.fee_growth_inside_0_last = 0;
-        position.fee_growth_inside_1_last = 0;
+        let event = ctx.accounts.handler(tick_lower, tick_upper)?;
+        emit_cpi!(event);
         Ok(())
     }
-}
 
-// Define all account structs in lib.rs for Anchor macro to find
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
-
-    pub token_0_mint: InterfaceAccount<'info, Mint>,
-    pub token_1_mint: InterfaceAccount<'info, Mint>,
-
-    pub token_program: Interface<'info, TokenInterface>,
-    pub system_program: Program<'info, System>,
-
-    #[account(
-        init, 
-        payer = signer, 
-        space = 8 + LpPoolStateShape::INIT_SPACE, 
-        seeds = [b"pool_state_v1", token_0_mint.key().as_ref(), token_1_mint.key().as_ref()], 
-        bump
-    )]
-    pub pool_state_account: Account<'info, LpPoolStateShape>,
-
-    #[account(
-        init, 
-        payer = signer, 
-        token::mint = token_0_mint, 
-        token::authority = pool_state_account, 
-        token::token_program = token_program, 
-        seeds = [b"token_0_vault", token_0_mint.key().as_ref()], 
-        bump
-    )]
-    pub token_0_vault: InterfaceAccount<'info, TokenAccount>,
-
-    #[account(
-        init, 
-        payer = signer, 
-        token::mint = token_1_mint, 
-        token::authority = pool_state_account, 
-        token::token_program = token_program, 
-        seeds = [b"token_1_vault", token_1_mint.key().as_ref()], 
-        bump
-    )]
-    pub token_1_vault: InterfaceAccount<'info, TokenAccount>,
-}
+    pub fn open_position_with_nft(
+        ctx: Context<OpenPositionWithNft>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(tick_lower, tick_upper)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn set_delegate(
+        ctx: Context<UpdatePositionDelegate>,
+        delegate: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        let event = ctx.accounts.set_delegate(delegate, permissions)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn revoke_delegate(ctx: Context<UpdatePositionDelegate>) -> Result<()> {
+        let event = ctx.accounts.revoke_delegate()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        let event = ctx.accounts.close_position()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn increase_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, IncreaseLiquidity<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+        let event = ctx.accounts.increase_liquidity(
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            ctx.remaining_accounts,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn decrease_liquidity(
+        ctx: Context<DecreaseLiquidity>,
+        liquidity_delta: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    ) -> Result<()> {
+        let event = ctx
+            .accounts
+            .decrease_liquidity(liquidity_delta, amount_0_min, amount_1_min)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn collect_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectFees<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        let event = ctx.accounts.collect_fees(
+            amount_0_requested,
+            amount_1_requested,
+            ctx.remaining_accounts,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn collect_protocol_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectProtocolFees<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        let event = ctx.accounts.collect_protocol_fees(
+            amount_0_requested,
+            amount_1_requested,
+            ctx.remaining_accounts,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn initialize_reward(ctx: Context<InitializeReward>, reward_index: u8) -> Result<()> {
+        let event = ctx.accounts.handler(reward_index)?;
+        emit_cpi!(event);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-#[instruction(tick_lower: i32, tick_upper: i32)]
-pub struct OpenPosition<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
+    pub fn set_reward_emissions(
+        ctx: Context<SetRewardEmissions>,
+        reward_index: u8,
+        emissions_per_second_x64: u128,
+    ) -> Result<()> {
+        let event = ctx
+            .accounts
+            .handler(reward_index, emissions_per_second_x64)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn collect_reward<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectReward<'info>>,
+        reward_index: u8,
+        amount_requested: u64,
+    ) -> Result<()> {
+        let event =
+            ctx.accounts
+                .collect_reward(reward_index, amount_requested, ctx.remaining_accounts)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        sqrt_price_limit_x64: u128,
+    ) -> Result<()> {
+        let event = ctx.accounts.swap(
+            ctx.remaining_accounts,
+            amount_in,
+            minimum_amount_out,
+            sqrt_price_limit_x64,
+            true,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
+    pub fn swap_exact_out<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        amount_out: u64,
+        maximum_amount_in: u64,
+        sqrt_price_limit_x64: u128,
+    ) -> Result<()> {
+        let event = ctx.accounts.swap(
+            ctx.remaining_accounts,
+            amount_out,
+            maximum_amount_in,
+            sqrt_price_limit_x64,
+            false,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
 
-    pub pool_state_account: Account<'info, LpPoolStateShape>,
+    pub fn increase_observation_cardinality(
+        ctx: Context<IncreaseObservationCardinality>,
+        cardinality_next: u16,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(cardinality_next)?;
+        emit_cpi!(event);
+        Ok(())
+    }
 
-    #[account(
-        init, 
-        payer = signer, 
-        space = 8 + Position::INIT_SPACE, 
-        seeds = [b"position", signer.key().as_ref(), pool_state_account.key().as_ref(), &tick_upper.to_le_bytes(), &tick_lower.to_le_bytes()], 
-        bump
-    )]
-    pub position: Account<'info, Position>,
+    pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+        ctx.accounts.handler(seconds_agos)
+    }
 }
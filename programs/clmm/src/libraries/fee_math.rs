@@ -1,9 +1,12 @@
-pub const Q64: u128 = 1u128 << 64;
+use anchor_lang::prelude::*;
+
+use super::full_math::mul_shr;
+use crate::errors::ClmmError;
+use crate::states::LpPoolStateShape;
 use crate::states::Position;
 use crate::states::TickState;
-use crate::states::LpPoolStateShape;
 
-//get the fee growth
+//get the fee growth, growth accumulators wrap so differences are taken modulo 2^128
 pub fn get_fee_growth_below(
     tick_current: i32,
     tick_lower: i32,
@@ -11,9 +14,9 @@ pub fn get_fee_growth_below(
     fee_growth_outside: u128,
 ) -> u128 {
     if tick_current >= tick_lower {
-        return fee_growth_outside;
+        fee_growth_outside
     } else {
-        return fee_growth_global - fee_growth_outside;
+        fee_growth_global.wrapping_sub(fee_growth_outside)
     }
 }
 
@@ -24,9 +27,9 @@ pub fn get_fee_growth_above(
     fee_growth_outside: u128,
 ) -> u128 {
     if tick_current < tick_upper {
-        return fee_growth_outside;
+        fee_growth_outside
     } else {
-        return fee_growth_global - fee_growth_outside;
+        fee_growth_global.wrapping_sub(fee_growth_outside)
     }
 }
 
@@ -51,32 +54,31 @@ pub fn get_fee_growth_inside(
         fee_growth_global,
         fee_growth_outside_upper,
     );
-    let fee_growth_inside = fee_growth_global
-        .wrapping_sub(fee_growth_below)
-        .wrapping_sub(fee_growth_above);
 
-    fee_growth_inside
+    fee_growth_global
+        .wrapping_sub(fee_growth_below)
+        .wrapping_sub(fee_growth_above)
 }
 
 pub fn calculate_tokens_owed(
     fee_growth_inside_current: u128,
     fee_growth_inside_last: u128,
     liquidity: u128,
-) -> u64 {
+) -> Result<u64> {
     //handle underflow
     let fee_growth_delta = fee_growth_inside_current.wrapping_sub(fee_growth_inside_last);
 
-    //tokens owed
-    let tokens_owed = (fee_growth_delta * liquidity) / Q64;
+    //tokens owed, fee growth is Q64.64 per unit of liquidity
+    let tokens_owed = mul_shr(fee_growth_delta, liquidity, 64)?;
 
-    return tokens_owed as u64;
+    u64::try_from(tokens_owed).map_err(|_| error!(ClmmError::AmountOverflow))
 }
 pub fn update_position_fees(
     position: &mut Position,
     pool: &LpPoolStateShape,
     tick_lower_state: &TickState,
     tick_upper_state: &TickState,
-) {
+) -> Result<()> {
     let tick_lower = position.tick_lower;
     let tick_upper = position.tick_upper;
     let fee_growth_inside_0 = get_fee_growth_inside(
@@ -93,7 +95,7 @@ pub fn update_position_fees(
         fee_growth_inside_0,
         position.fee_growth_inside_0_last,
         position.liquidity,
-    );
+    )?;
 
     let fee_growth_inside_1 = get_fee_growth_inside(
         tick_lower,
@@ -109,11 +111,18 @@ pub fn update_position_fees(
         fee_growth_inside_1,
         position.fee_growth_inside_1_last,
         position.liquidity,
-    );
+    )?;
 
     //update positions
-    position.tokens_owed_0 += token_0_owed;
-    position.tokens_owed_1 += token_1_owed;
+    position.tokens_owed_0 = position
+        .tokens_owed_0
+        .checked_add(token_0_owed)
+        .ok_or(error!(ClmmError::AmountOverflow))?;
+    position.tokens_owed_1 = position
+        .tokens_owed_1
+        .checked_add(token_1_owed)
+        .ok_or(error!(ClmmError::AmountOverflow))?;
     position.fee_growth_inside_0_last = fee_growth_inside_0;
     position.fee_growth_inside_1_last = fee_growth_inside_1;
+    Ok(())
 }
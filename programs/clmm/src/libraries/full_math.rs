@@ -0,0 +1,95 @@
+// Full precision math for CLMM
+// Products are widened to 256 (or 512) bits so intermediate values never overflow
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+
+// kept apart from the anchor prelude, whose Result alias clashes with the macro
+#[allow(clippy::manual_div_ceil)]
+mod big_num {
+    use uint::construct_uint;
+
+    construct_uint! {
+        pub struct U256(4);
+    }
+
+    construct_uint! {
+        pub struct U512(8);
+    }
+}
+
+pub use big_num::U256;
+use big_num::U512;
+
+fn widen(value: U256) -> U512 {
+    let mut limbs = [0u64; 8];
+    limbs[..4].copy_from_slice(&value.0);
+    U512(limbs)
+}
+
+fn narrow(value: U512) -> Result<U256> {
+    require!(
+        value.0[4..].iter().all(|limb| *limb == 0),
+        ClmmError::MulDivOverflow
+    );
+    let mut limbs = [0u64; 4];
+    limbs.copy_from_slice(&value.0[..4]);
+    Ok(U256(limbs))
+}
+
+fn mul_div_u512(a: U256, b: U256, denominator: U256, round_up: bool) -> Result<U256> {
+    require!(!denominator.is_zero(), ClmmError::DivisionByZero);
+    let product = widen(a) * widen(b);
+    let denominator = widen(denominator);
+    let (quotient, remainder) = product.div_mod(denominator);
+    let result = if round_up && !remainder.is_zero() {
+        quotient + 1
+    } else {
+        quotient
+    };
+    narrow(result)
+}
+
+/// Convert a 256-bit value back to u128
+pub fn to_u128(value: U256) -> Result<u128> {
+    u128::try_from(value).map_err(|_| error!(ClmmError::MulDivOverflow))
+}
+
+/// Convert a 256-bit value to a token amount
+pub fn to_u64(value: U256) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(ClmmError::AmountOverflow))
+}
+
+/// (a × b) / denominator, rounded down
+pub fn mul_div_floor(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    to_u128(mul_div_floor_u256(
+        U256::from(a),
+        U256::from(b),
+        U256::from(denominator),
+    )?)
+}
+
+/// (a × b) / denominator, rounded up
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    to_u128(mul_div_ceil_u256(
+        U256::from(a),
+        U256::from(b),
+        U256::from(denominator),
+    )?)
+}
+
+/// (a × b) >> offset, rounded down
+pub fn mul_shr(a: u128, b: u128, offset: u8) -> Result<u128> {
+    to_u128((U256::from(a) * U256::from(b)) >> offset)
+}
+
+/// 256-bit (a × b) / denominator, rounded down
+pub fn mul_div_floor_u256(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    mul_div_u512(a, b, denominator, false)
+}
+
+/// 256-bit (a × b) / denominator, rounded up
+pub fn mul_div_ceil_u256(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    mul_div_u512(a, b, denominator, true)
+}
@@ -1,53 +1,96 @@
+use anchor_lang::prelude::*;
+
+use super::full_math::{mul_div_floor, mul_div_floor_u256, to_u64, U256};
+use crate::errors::ClmmError;
+
 pub const Q64: u128 = 1u128 << 64;
 
+/// Apply a signed liquidity_net to a liquidity amount
+pub fn add_delta(liquidity: u128, delta: i128) -> Result<u128> {
+    if delta >= 0 {
+        liquidity
+            .checked_add(delta as u128)
+            .ok_or(error!(ClmmError::MathOverflow))
+    } else {
+        liquidity
+            .checked_sub(delta.unsigned_abs())
+            .ok_or(error!(ClmmError::MathUnderflow))
+    }
+}
+
+/// Remove a signed liquidity_net from a liquidity amount
+pub fn sub_delta(liquidity: u128, delta: i128) -> Result<u128> {
+    if delta >= 0 {
+        liquidity
+            .checked_sub(delta as u128)
+            .ok_or(error!(ClmmError::MathUnderflow))
+    } else {
+        liquidity
+            .checked_add(delta.unsigned_abs())
+            .ok_or(error!(ClmmError::MathOverflow))
+    }
+}
+
 pub fn get_liquidity_from_amount_0(
     sqrt_price_lower: u128,
     sqrt_price_upper: u128,
     amount_0: u64,
-) -> u128 {
+) -> Result<u128> {
     //intermediate
-    let intermediate = (sqrt_price_lower * sqrt_price_upper) / Q64;
-    let difference = sqrt_price_upper - sqrt_price_lower;
+    let intermediate = mul_div_floor(sqrt_price_lower, sqrt_price_upper, Q64)?;
+    let difference = sqrt_price_upper
+        .checked_sub(sqrt_price_lower)
+        .ok_or(error!(ClmmError::MathUnderflow))?;
 
     //liquidity
-    let liquidity = (amount_0 as u128) * intermediate / difference;
-
-    //return liquidity
-    liquidity
+    mul_div_floor(amount_0 as u128, intermediate, difference)
 }
 
 pub fn get_liquidity_from_amount_1(
     sqrt_price_lower: u128,
     sqrt_price_upper: u128,
     amount_1: u64,
-) -> u128 {
+) -> Result<u128> {
     //difference
-    let difference = sqrt_price_upper - sqrt_price_lower;
+    let difference = sqrt_price_upper
+        .checked_sub(sqrt_price_lower)
+        .ok_or(error!(ClmmError::MathUnderflow))?;
 
     //liquidity
-    let liquidity = (amount_1 as u128 * Q64) / difference as u128;
-
-    return liquidity as u128;
+    mul_div_floor(amount_1 as u128, Q64, difference)
 }
 
 pub fn get_amounts_0_from_liquidity(
     sqrt_price_lower: u128,
     sqrt_price_upper: u128,
     liquidity: u128,
-) -> u64 {
-    let difference = sqrt_price_upper - sqrt_price_lower;
+) -> Result<u64> {
+    let difference = sqrt_price_upper
+        .checked_sub(sqrt_price_lower)
+        .ok_or(error!(ClmmError::MathUnderflow))?;
 
-    let amount_0 = (liquidity * difference * Q64) / (sqrt_price_upper * sqrt_price_lower);
-    return amount_0 as u64;
+    //L × diff × 2^64 / (upper × lower)
+    let amount_0 = mul_div_floor_u256(
+        U256::from(liquidity) * U256::from(difference),
+        U256::from(Q64),
+        U256::from(sqrt_price_upper) * U256::from(sqrt_price_lower),
+    )?;
+    to_u64(amount_0)
 }
 
 pub fn get_amounts_1_from_liquidity(
     sqrt_price_lower: u128,
     sqrt_price_upper: u128,
     liquidity: u128,
-) -> u64 {
-    let difference = sqrt_price_upper - sqrt_price_lower;
+) -> Result<u64> {
+    let difference = sqrt_price_upper
+        .checked_sub(sqrt_price_lower)
+        .ok_or(error!(ClmmError::MathUnderflow))?;
 
-    let amount_1 = (liquidity * difference) / Q64;
-    return amount_1 as u64;
+    let amount_1 = mul_div_floor_u256(
+        U256::from(liquidity),
+        U256::from(difference),
+        U256::from(Q64),
+    )?;
+    to_u64(amount_1)
 }
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::Pubkey;
+
+/// Sort a mint pair into pool order (token_0 < token_1)
+/// Returns: (token_0_mint, token_1_mint, invert_price)
+/// `invert_price` is true when `mint_a` ends up as token_1, so a price quoted
+/// as mint_b per mint_a must be inverted to match the pool's token_1/token_0 price
+pub fn sort_mints(mint_a: Pubkey, mint_b: Pubkey) -> (Pubkey, Pubkey, bool) {
+    if mint_a <= mint_b {
+        (mint_a, mint_b, false)
+    } else {
+        (mint_b, mint_a, true)
+    }
+}
+
+/// Pool creation accepts a pair only in strict pool order, so equal mints are rejected
+pub fn is_pool_order(token_0_mint: &Pubkey, token_1_mint: &Pubkey) -> bool {
+    token_0_mint < token_1_mint
+}
@@ -1,6 +1,9 @@
 pub mod fee_math;
 pub mod full_math;
 pub mod liquidity_math;
+pub mod mint_order;
+pub mod oracle_math;
+pub mod reward_math;
 pub mod swap_math;
 pub mod tick_math;
 
@@ -14,10 +17,16 @@ pub use liquidity_math::{
     get_liquidity_from_amount_1,
 };
 
-pub use tick_math::{get_sqrt_price_at_tick, get_tick_at_sqrt_price};
+pub use mint_order::{is_pool_order, sort_mints};
+
+pub use oracle_math::get_arithmetic_mean_tick;
+
+pub use reward_math::{get_reward_growths_inside, update_position_rewards};
+
+pub use tick_math::{check_ticks_range, get_sqrt_price_at_tick, get_tick_at_sqrt_price};
 
 pub use swap_math::{
     compute_swap_step, find_next_initialized_tick, get_amount_0_delta, get_amount_1_delta,
-    get_next_sqrt_price_from_input, Q64,
+    get_next_sqrt_price_from_input, get_next_sqrt_price_from_output, FEE_RATE_DENOMINATOR, Q64,
 };
 
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+
+/// Arithmetic mean tick over a window from two `observe` tick cumulatives
+/// Rounded towards negative infinity, like the cumulatives themselves
+pub fn get_arithmetic_mean_tick(
+    tick_cumulative_start: i64,
+    tick_cumulative_end: i64,
+    seconds_elapsed: u32,
+) -> Result<i32> {
+    require!(seconds_elapsed > 0, ClmmError::InvalidTwapWindow);
+    let tick_cumulative_delta = tick_cumulative_end.wrapping_sub(tick_cumulative_start);
+    Ok(tick_cumulative_delta.div_euclid(seconds_elapsed as i64) as i32)
+}
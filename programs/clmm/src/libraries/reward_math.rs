@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use super::fee_math::{calculate_tokens_owed, get_fee_growth_inside};
+use crate::errors::ClmmError;
+use crate::states::pool::{LpPoolStateShape, REWARD_NUM};
+use crate::states::{Position, TickState};
+
+/// Reward growth inside a range, per reward slot
+/// Same below/above split as fee growth
+pub fn get_reward_growths_inside(
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_current: i32,
+    reward_growths_global: [u128; REWARD_NUM],
+    tick_lower_state: &TickState,
+    tick_upper_state: &TickState,
+) -> [u128; REWARD_NUM] {
+    let outside_lower = tick_lower_state.reward_growths_outside;
+    let outside_upper = tick_upper_state.reward_growths_outside;
+    let mut reward_growths_inside = [0u128; REWARD_NUM];
+    for i in 0..REWARD_NUM {
+        reward_growths_inside[i] = get_fee_growth_inside(
+            tick_lower,
+            tick_upper,
+            tick_current,
+            reward_growths_global[i],
+            outside_lower[i],
+            outside_upper[i],
+        );
+    }
+    reward_growths_inside
+}
+
+/// Credit rewards earned since the last checkpoint, pool rewards must be up to date
+pub fn update_position_rewards(
+    position: &mut Position,
+    pool: &LpPoolStateShape,
+    tick_lower_state: &TickState,
+    tick_upper_state: &TickState,
+) -> Result<()> {
+    let reward_growths_inside = get_reward_growths_inside(
+        position.tick_lower,
+        position.tick_upper,
+        pool.tick_current,
+        pool.reward_growths_global(),
+        tick_lower_state,
+        tick_upper_state,
+    );
+
+    for (i, reward_info) in position.reward_infos.iter_mut().enumerate() {
+        if !pool.reward_infos[i].initialized() {
+            continue;
+        }
+
+        //same Q64.64 per liquidity scaling as fees
+        let amount_owed = calculate_tokens_owed(
+            reward_growths_inside[i],
+            reward_info.growth_inside_last_x64,
+            position.liquidity,
+        )?;
+        reward_info.amount_owed = reward_info
+            .amount_owed
+            .checked_add(amount_owed)
+            .ok_or(error!(ClmmError::AmountOverflow))?;
+        reward_info.growth_inside_last_x64 = reward_growths_inside[i];
+    }
+    Ok(())
+}
@@ -1,66 +1,116 @@
 use anchor_lang::prelude::*;
 
+use super::full_math::{
+    mul_div_ceil, mul_div_ceil_u256, mul_div_floor, mul_div_floor_u256, to_u128, to_u64, U256,
+};
+use crate::errors::ClmmError;
+
 pub const Q64: u128 = 1u128 << 64;
 
+// fee rates are expressed in hundredths of a basis point
+pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
+
 /// Computes the result of a swap step
+/// A positive amount_remaining is an exact input, a negative one an exact output
 /// Returns: (sqrt_price_next, amount_in, amount_out, fee_amount)
 pub fn compute_swap_step(
     sqrt_price_current: u128,
     sqrt_price_target: u128,
     liquidity: u128,
-    amount_remaining: u64,
+    amount_remaining: i64,
     fee_rate: u32,
     zero_for_one: bool,
-) -> (u128, u64, u64, u64) {
-    let fee_complement = 1_000_000u64 - fee_rate as u64;
+) -> Result<(u128, u64, u64, u64)> {
+    let exact_in = amount_remaining >= 0;
+    let amount_remaining_abs = amount_remaining.unsigned_abs();
+    let fee_complement = FEE_RATE_DENOMINATOR
+        .checked_sub(fee_rate)
+        .ok_or(error!(ClmmError::InvalidFeeRate))? as u64;
 
-    // Apply fee to get effective amount
-    let amount_remaining_less_fee = (amount_remaining * fee_complement) / 1_000_000;
+    // Determine if we reach target price or exhaust amount first
+    let sqrt_price_next = if exact_in {
+        // Apply fee to get effective amount
+        let amount_remaining_less_fee = mul_div_floor(
+            amount_remaining_abs as u128,
+            fee_complement as u128,
+            FEE_RATE_DENOMINATOR as u128,
+        )? as u64;
 
-    // Calculate max amount possible with current liquidity to reach target
-    let amount_in_max = if zero_for_one {
-        // token0 in, need delta to move price down
-        get_amount_0_delta(sqrt_price_target, sqrt_price_current, liquidity, true)
-    } else {
-        // token1 in, need delta to move price up
-        get_amount_1_delta(sqrt_price_current, sqrt_price_target, liquidity, true)
-    };
+        // Calculate max amount possible with current liquidity to reach target
+        // kept in 256 bits, a target beyond u64 is simply not reached
+        let amount_in_max = if zero_for_one {
+            // token0 in, need delta to move price down
+            get_amount_0_delta_u256(sqrt_price_target, sqrt_price_current, liquidity, true)?
+        } else {
+            // token1 in, need delta to move price up
+            get_amount_1_delta_u256(sqrt_price_current, sqrt_price_target, liquidity, true)?
+        };
 
-    // Determine if we reach target price or exhaust amount first
-    let (sqrt_price_next, amount_in) = if amount_remaining_less_fee >= amount_in_max {
-        // We reach the target price
-        (sqrt_price_target, amount_in_max)
+        if U256::from(amount_remaining_less_fee) >= amount_in_max {
+            sqrt_price_target
+        } else {
+            get_next_sqrt_price_from_input(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        }
     } else {
-        // We don't reach target - calculate new price from amount
-        let sqrt_price_next = get_next_sqrt_price_from_input(
-            sqrt_price_current,
-            liquidity,
-            amount_remaining_less_fee,
-            zero_for_one,
-        );
-        (sqrt_price_next, amount_remaining_less_fee)
+        // Calculate max output available before reaching target
+        let amount_out_max = if zero_for_one {
+            // token1 out, price moves down
+            get_amount_1_delta_u256(sqrt_price_target, sqrt_price_current, liquidity, false)?
+        } else {
+            // token0 out, price moves up
+            get_amount_0_delta_u256(sqrt_price_current, sqrt_price_target, liquidity, false)?
+        };
+
+        if U256::from(amount_remaining_abs) >= amount_out_max {
+            sqrt_price_target
+        } else {
+            get_next_sqrt_price_from_output(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining_abs,
+                zero_for_one,
+            )?
+        }
     };
 
-    // Calculate amount_out based on price movement
-    let amount_out = if zero_for_one {
-        // Going down: output is token1
-        get_amount_1_delta(sqrt_price_next, sqrt_price_current, liquidity, false)
+    // Calculate amounts based on price movement
+    let (amount_in, mut amount_out) = if zero_for_one {
+        // Going down: input is token0, output is token1
+        (
+            get_amount_0_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?,
+            get_amount_1_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?,
+        )
     } else {
-        // Going up: output is token0
-        get_amount_0_delta(sqrt_price_current, sqrt_price_next, liquidity, false)
+        // Going up: input is token1, output is token0
+        (
+            get_amount_1_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?,
+            get_amount_0_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?,
+        )
     };
 
+    // Never pay out more than was asked for
+    if !exact_in && amount_out > amount_remaining_abs {
+        amount_out = amount_remaining_abs;
+    }
+
     // Calculate fee
-    let fee_amount = if sqrt_price_next != sqrt_price_target {
+    let fee_amount = if exact_in && sqrt_price_next != sqrt_price_target {
         // Didn't reach target, fee is remainder
-        amount_remaining - amount_in
+        amount_remaining_abs
+            .checked_sub(amount_in)
+            .ok_or(error!(ClmmError::MathUnderflow))?
     } else {
-        // Reached target, calculate fee from amount_in
-        let fee = (amount_in as u128 * fee_rate as u128 + 999_999) / 1_000_000;
-        fee as u64
+        // Fee on top of the net input amount
+        let fee = mul_div_ceil(amount_in as u128, fee_rate as u128, fee_complement as u128)?;
+        u64::try_from(fee).map_err(|_| error!(ClmmError::AmountOverflow))?
     };
 
-    (sqrt_price_next, amount_in, amount_out, fee_amount)
+    Ok((sqrt_price_next, amount_in, amount_out, fee_amount))
 }
 
 /// Get amount of token0 needed for a price change
@@ -70,7 +120,22 @@ pub fn get_amount_0_delta(
     sqrt_price_b: u128,
     liquidity: u128,
     round_up: bool,
-) -> u64 {
+) -> Result<u64> {
+    to_u64(get_amount_0_delta_u256(
+        sqrt_price_a,
+        sqrt_price_b,
+        liquidity,
+        round_up,
+    )?)
+}
+
+/// get_amount_0_delta without narrowing to a token amount
+pub fn get_amount_0_delta_u256(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256> {
     // Ensure a < b
     let (sqrt_price_lower, sqrt_price_upper) = if sqrt_price_a < sqrt_price_b {
         (sqrt_price_a, sqrt_price_b)
@@ -78,13 +143,13 @@ pub fn get_amount_0_delta(
         (sqrt_price_b, sqrt_price_a)
     };
 
-    let numerator = liquidity * (sqrt_price_upper - sqrt_price_lower);
-    let denominator = sqrt_price_lower * sqrt_price_upper / Q64;
+    let numerator = U256::from(liquidity) * U256::from(sqrt_price_upper - sqrt_price_lower);
+    let denominator = U256::from(sqrt_price_lower) * U256::from(sqrt_price_upper);
 
     if round_up {
-        ((numerator + denominator - 1) / denominator) as u64
+        mul_div_ceil_u256(numerator, U256::from(Q64), denominator)
     } else {
-        (numerator / denominator) as u64
+        mul_div_floor_u256(numerator, U256::from(Q64), denominator)
     }
 }
 
@@ -95,7 +160,22 @@ pub fn get_amount_1_delta(
     sqrt_price_b: u128,
     liquidity: u128,
     round_up: bool,
-) -> u64 {
+) -> Result<u64> {
+    to_u64(get_amount_1_delta_u256(
+        sqrt_price_a,
+        sqrt_price_b,
+        liquidity,
+        round_up,
+    )?)
+}
+
+/// get_amount_1_delta without narrowing to a token amount
+pub fn get_amount_1_delta_u256(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256> {
     // Ensure a < b
     let (sqrt_price_lower, sqrt_price_upper) = if sqrt_price_a < sqrt_price_b {
         (sqrt_price_a, sqrt_price_b)
@@ -103,13 +183,12 @@ pub fn get_amount_1_delta(
         (sqrt_price_b, sqrt_price_a)
     };
 
-    let diff = sqrt_price_upper - sqrt_price_lower;
-    let result = liquidity * diff / Q64;
+    let diff = U256::from(sqrt_price_upper - sqrt_price_lower);
 
     if round_up {
-        ((liquidity * diff + Q64 - 1) / Q64) as u64
+        mul_div_ceil_u256(U256::from(liquidity), diff, U256::from(Q64))
     } else {
-        result as u64
+        mul_div_floor_u256(U256::from(liquidity), diff, U256::from(Q64))
     }
 }
 
@@ -119,55 +198,92 @@ pub fn get_next_sqrt_price_from_input(
     liquidity: u128,
     amount_in: u64,
     zero_for_one: bool,
-) -> u128 {
+) -> Result<u128> {
     if zero_for_one {
         // Adding token0, price goes down
-        // √P_new = L × √P / (L + Δx × √P)
-        let product = sqrt_price * amount_in as u128;
-        let denominator = liquidity * Q64 + product;
-        (liquidity * Q64 * sqrt_price) / denominator
+        // √P_new = L × √P / (L + Δx × √P), rounded up
+        let numerator = U256::from(liquidity) << 64;
+        let product = U256::from(amount_in) * U256::from(sqrt_price);
+        let denominator = numerator + product;
+        to_u128(mul_div_ceil_u256(
+            numerator,
+            U256::from(sqrt_price),
+            denominator,
+        )?)
     } else {
         // Adding token1, price goes up
-        // √P_new = √P + Δy / L
-        sqrt_price + (amount_in as u128 * Q64) / liquidity
+        // √P_new = √P + Δy / L, rounded down
+        let quotient = mul_div_floor(amount_in as u128, Q64, liquidity)?;
+        sqrt_price
+            .checked_add(quotient)
+            .ok_or(error!(ClmmError::SqrtPriceOutOfRange))
     }
 }
 
-/// Find next initialized tick in the direction of the swap
+/// Calculate new sqrt_price after taking amount of output token out
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_out: u64,
+    zero_for_one: bool,
+) -> Result<u128> {
+    if zero_for_one {
+        // Removing token1, price goes down
+        // √P_new = √P - Δy / L, rounded down
+        let quotient = mul_div_ceil(amount_out as u128, Q64, liquidity)?;
+        require!(sqrt_price > quotient, ClmmError::SqrtPriceOutOfRange);
+        Ok(sqrt_price - quotient)
+    } else {
+        // Removing token0, price goes up
+        // √P_new = L × √P / (L - Δx × √P), rounded up
+        let numerator = U256::from(liquidity) << 64;
+        let product = U256::from(amount_out) * U256::from(sqrt_price);
+        require!(numerator > product, ClmmError::SqrtPriceOutOfRange);
+        let denominator = numerator - product;
+        to_u128(mul_div_ceil_u256(
+            numerator,
+            U256::from(sqrt_price),
+            denominator,
+        )?)
+    }
+}
+
+/// Find next initialized tick in the direction of the swap, within one tick array
+/// zero_for_one looks at ticks <= current_tick, otherwise ticks > current_tick
+/// Returns: (tick, index_in_array), or None when the rest of the array is uninitialized
 pub fn find_next_initialized_tick(
     ticks: &[crate::states::tick::TickState; 60],
     start_tick_index: i32,
     current_tick: i32,
     tick_spacing: i32,
     zero_for_one: bool,
-) -> Option<(i32, usize, bool)> {
-    // Calculate current position in array
-    let current_index_in_array = (current_tick - start_tick_index) / tick_spacing;
-
+) -> Option<(i32, usize)> {
     if zero_for_one {
         // Search left (lower ticks)
-        for i in (0..=current_index_in_array as usize).rev() {
-            if i < 60 && ticks[i].initialized == 1 {
+        if current_tick < start_tick_index {
+            return None;
+        }
+        let last_index = ((current_tick - start_tick_index) / tick_spacing).min(59) as usize;
+        for i in (0..=last_index).rev() {
+            if ticks[i].initialized == 1 {
                 let tick = start_tick_index + (i as i32 * tick_spacing);
-                return Some((tick, i, true));
+                return Some((tick, i));
             }
         }
     } else {
         // Search right (higher ticks)
-        for i in (current_index_in_array as usize + 1)..60 {
-            if ticks[i].initialized == 1 {
+        let first_index = if current_tick < start_tick_index {
+            0
+        } else {
+            ((current_tick - start_tick_index) / tick_spacing + 1) as usize
+        };
+        for (i, tick_state) in ticks.iter().enumerate().skip(first_index) {
+            if tick_state.initialized == 1 {
                 let tick = start_tick_index + (i as i32 * tick_spacing);
-                return Some((tick, i, true));
+                return Some((tick, i));
             }
         }
     }
 
-    // No initialized tick found, return boundary
-    if zero_for_one {
-        let tick = start_tick_index;
-        Some((tick, 0, false))
-    } else {
-        let tick = start_tick_index + 59 * tick_spacing;
-        Some((tick, 59, false))
-    }
+    None
 }
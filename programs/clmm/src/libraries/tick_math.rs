@@ -3,6 +3,8 @@
 
 use anchor_lang::prelude::*;
 
+use crate::errors::ClmmError;
+
 // Tick bounds
 pub const MIN_TICK: i32 = -443636;
 pub const MAX_TICK: i32 = 443636;
@@ -19,13 +21,13 @@ const Q64: u128 = 1u128 << 64;
 /// This uses precomputed magic numbers for efficient calculation
 pub fn get_sqrt_price_at_tick(tick: i32) -> u128 {
     let abs_tick = tick.unsigned_abs();
-    
+
     // Start with Q64 (1.0 in Q64.64 format)
     let mut ratio = Q64;
-    
+
     // Multiply by precomputed values for each bit of abs_tick
     // These magic numbers represent sqrt(1.0001^(2^i)) in Q64.64 format
-    
+
     // i = 0: sqrt(1.0001^1)
     if abs_tick & 0x1 != 0 {
         ratio = (ratio * 18445821805675392311u128) >> 64;
@@ -102,31 +104,70 @@ pub fn get_sqrt_price_at_tick(tick: i32) -> u128 {
     if abs_tick & 0x40000 != 0 {
         ratio = (ratio * 37481735321082u128) >> 64;
     }
-    
+
     // If tick is positive, we need to invert (1/ratio)
     if tick > 0 {
         ratio = u128::MAX / ratio;
     }
-    
+
     ratio
 }
 
 /// Get tick at a given sqrt price (Q64.64)
-/// This is the inverse of get_sqrt_price_at_tick
+/// This is the inverse of get_sqrt_price_at_tick: returns the greatest tick
+/// whose sqrt price is <= sqrt_price_x64, clamped to [MIN_TICK, MAX_TICK]
 pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> i32 {
-    // Use binary search to find the tick
-    // log_1.0001(sqrt_price) = log2(sqrt_price) / log2(1.0001)
-    
-    // Find log2(sqrt_price_x64 / 2^64) = log2(sqrt_price_x64) - 64
-    let msb = 127 - sqrt_price_x64.leading_zeros() as i32;
-    let log2_approx = msb - 64;
-    
-    // log2(1.0001) ≈ 0.000144262
-    // So tick ≈ log2(sqrt_price) / 0.000144262 = log2(sqrt_price) * 6931.47
-    // We multiply by 2 because sqrt_price = price^0.5
-    let tick_approx = (log2_approx as i64 * 6932) as i32;
-    
-    // The approximation can be off, so we verify and adjust
-    // For now, return approximation (exact calculation would require more precision)
-    tick_approx
+    // log_√1.0001(sqrt_price) = log2(sqrt_price) / log2(√1.0001)
+
+    // Integer part: log2(sqrt_price_x64 / 2^64) = msb - 64
+    let msb = 127 - sqrt_price_x64.leading_zeros();
+    let log2_integer_x32 = (msb as i128 - 64) << 32;
+
+    // Fractional part: normalise to r in [1, 2) as Q1.63, then square repeatedly
+    // Each squaring that lands in [2, 4) contributes the next bit of log2(r)
+    let mut r = if msb >= 63 {
+        sqrt_price_x64 >> (msb - 63)
+    } else {
+        sqrt_price_x64 << (63 - msb)
+    };
+    let mut log2_fraction_x32: i128 = 0;
+    let mut bit: i128 = 1 << 31;
+    for _ in 0..16 {
+        r *= r;
+        let is_r_more_than_two = (r >> 127) as u32;
+        r >>= 63 + is_r_more_than_two;
+        log2_fraction_x32 += bit * is_r_more_than_two as i128;
+        bit >>= 1;
+    }
+    let log2_x32 = log2_integer_x32 + log2_fraction_x32;
+
+    // Change of base: 1 / log2(√1.0001) ≈ 13863.5 in Q32.32
+    // 16 fractional bits keep the estimate within one tick of the answer
+    let log_sqrt_10001_x64 = log2_x32 * 59543866431248i128;
+    let mut tick = (log_sqrt_10001_x64 >> 64) as i32;
+    tick = tick.clamp(MIN_TICK, MAX_TICK);
+
+    // Correct the estimate against the exact forward function
+    while tick > MIN_TICK && get_sqrt_price_at_tick(tick) > sqrt_price_x64 {
+        tick -= 1;
+    }
+    while tick < MAX_TICK && get_sqrt_price_at_tick(tick + 1) <= sqrt_price_x64 {
+        tick += 1;
+    }
+
+    tick
+}
+
+/// Validate a position range against the tick bounds and the pool's spacing
+pub fn check_ticks_range(tick_lower: i32, tick_upper: i32, tick_spacing: u16) -> Result<()> {
+    require!(tick_lower < tick_upper, ClmmError::TickInvalidOrder);
+    require!(tick_lower >= MIN_TICK, ClmmError::TickLowerOverflow);
+    require!(tick_upper <= MAX_TICK, ClmmError::TickUpperOverflow);
+
+    let tick_spacing = tick_spacing as i32;
+    require!(
+        tick_lower % tick_spacing == 0 && tick_upper % tick_spacing == 0,
+        ClmmError::TickAndSpacingNotMatch
+    );
+    Ok(())
 }
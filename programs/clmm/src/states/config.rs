@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+pub const AMM_CONFIG_SEED: &[u8] = b"amm_config";
+
+//transfer hook programs a fee tier can accept
+pub const MAX_TRANSFER_HOOK_PROGRAMS: usize = 4;
+
+//fee tier shared by every pool created against it
+#[account]
+#[derive(InitSpace)]
+pub struct AmmConfig {
+    //bump
+    pub bump: u8,
+    //fee tier index, part of the pda seeds
+    pub index: u16,
+    //admin allowed to update the tier
+    pub owner: Pubkey,
+
+    //fee configuration (in FEE_RATE_DENOMINATOR units)
+    pub trade_fee_rate: u32,
+    //share of the trade fee kept by the protocol
+    pub protocol_fee_rate: u32,
+
+    //tick spacing for pools in this tier
+    pub tick_spacing: u16,
+    //hook programs allowed on pool mints, default entries are unused
+    pub transfer_hook_programs: [Pubkey; MAX_TRANSFER_HOOK_PROGRAMS],
+}
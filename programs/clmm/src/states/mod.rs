@@ -1,8 +1,12 @@
+pub mod config;
+pub mod oracle;
 pub mod pool;
 pub mod position;
 pub mod swapstate;
 pub mod tick;
 
+pub use config::*;
+pub use oracle::*;
 pub use pool::*;
 pub use position::*;
 pub use tick::*;
@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::libraries::full_math::mul_div_floor;
+
+pub const OBSERVATION_SEED: &[u8] = b"observation";
+
+//upper bound for the configurable cardinality
+pub const OBSERVATION_CAPACITY: usize = 100;
+
+//price observation, cumulatives grow by tick and seconds / liquidity every second
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct Observation {
+    pub initialized: u8,
+    pub _padding: [u8; 7],
+    pub block_timestamp: u64,
+    pub slot: u64,
+    pub tick_cumulative: i64,
+    pub seconds_per_liquidity_cumulative_x64: u128,
+}
+
+impl Observation {
+    /// Extend the cumulatives to `timestamp` assuming `tick` and `liquidity` held since
+    pub fn transform(&self, timestamp: u64, tick: i32, liquidity: u128) -> Observation {
+        let elapsed = timestamp.saturating_sub(self.block_timestamp);
+        let tick_cumulative = self.tick_cumulative;
+        let seconds_per_liquidity_cumulative_x64 = self.seconds_per_liquidity_cumulative_x64;
+        Observation {
+            initialized: 1,
+            _padding: [0; 7],
+            block_timestamp: timestamp,
+            slot: self.slot,
+            tick_cumulative: tick_cumulative.wrapping_add(tick as i64 * elapsed as i64),
+            seconds_per_liquidity_cumulative_x64: seconds_per_liquidity_cumulative_x64
+                .wrapping_add(((elapsed as u128) << 64) / liquidity.max(1)),
+        }
+    }
+}
+
+//ring buffer of observations for one pool
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct ObservationState {
+    pub pool_id: Pubkey,
+    pub bump: u8,
+    pub _padding: [u8; 1],
+    //slot of the most recent observation
+    pub index: u16,
+    //slots in use, and the size the buffer grows to once it wraps
+    pub cardinality: u16,
+    pub cardinality_next: u16,
+    pub observations: [Observation; OBSERVATION_CAPACITY],
+}
+
+impl ObservationState {
+    /// Seed the buffer with a zero observation at pool creation
+    pub fn initialize(&mut self, pool_id: Pubkey, bump: u8, timestamp: u64, slot: u64) {
+        self.pool_id = pool_id;
+        self.bump = bump;
+        self.index = 0;
+        self.cardinality = 1;
+        self.cardinality_next = 1;
+        self.observations[0] = Observation {
+            initialized: 1,
+            block_timestamp: timestamp,
+            slot,
+            ..Default::default()
+        };
+    }
+
+    /// Record the tick and liquidity in force before the first swap of a slot
+    pub fn write(&mut self, timestamp: u64, slot: u64, tick: i32, liquidity: u128) {
+        let last = self.observations[self.index as usize];
+        let last_slot = last.slot;
+        let last_timestamp = last.block_timestamp;
+        //cumulatives only move when the clock does
+        if last_slot == slot || last_timestamp >= timestamp {
+            return;
+        }
+
+        //grow into the extra slots once the buffer wraps
+        let cardinality =
+            if self.cardinality_next > self.cardinality && self.index == self.cardinality - 1 {
+                self.cardinality_next
+            } else {
+                self.cardinality
+            };
+        let index = (self.index + 1) % cardinality;
+
+        let mut observation = last.transform(timestamp, tick, liquidity);
+        observation.slot = slot;
+        self.observations[index as usize] = observation;
+        self.index = index;
+        self.cardinality = cardinality;
+    }
+
+    /// Cumulatives `seconds_ago` before `now`, interpolated between observations
+    /// Returns: (tick_cumulative, seconds_per_liquidity_cumulative_x64)
+    pub fn observe_single(
+        &self,
+        now: u64,
+        seconds_ago: u32,
+        tick: i32,
+        liquidity: u128,
+    ) -> Result<(i64, u128)> {
+        let target = now
+            .checked_sub(seconds_ago as u64)
+            .ok_or(error!(ClmmError::ObservationTooOld))?;
+
+        //at or after the newest observation, extend it with the current state
+        let last = self.observations[self.index as usize];
+        let last_timestamp = last.block_timestamp;
+        if target >= last_timestamp {
+            let observation = last.transform(target, tick, liquidity);
+            return Ok((
+                observation.tick_cumulative,
+                observation.seconds_per_liquidity_cumulative_x64,
+            ));
+        }
+
+        //oldest observation is the next slot, or slot 0 while the buffer hasn't wrapped
+        let cardinality = self.cardinality as usize;
+        let mut oldest = self.observations[(self.index as usize + 1) % cardinality];
+        if oldest.initialized == 0 {
+            oldest = self.observations[0];
+        }
+        let oldest_timestamp = oldest.block_timestamp;
+        require!(oldest_timestamp <= target, ClmmError::ObservationTooOld);
+
+        let (before, after) = self.binary_search(target);
+        let before_timestamp = before.block_timestamp;
+        let after_timestamp = after.block_timestamp;
+        let (before_tick, before_seconds) = (
+            before.tick_cumulative,
+            before.seconds_per_liquidity_cumulative_x64,
+        );
+        let (after_tick, after_seconds) = (
+            after.tick_cumulative,
+            after.seconds_per_liquidity_cumulative_x64,
+        );
+        if target == before_timestamp {
+            return Ok((before_tick, before_seconds));
+        }
+        if target == after_timestamp {
+            return Ok((after_tick, after_seconds));
+        }
+
+        //tick was constant in between, so the cumulatives are linear
+        let interval = after_timestamp - before_timestamp;
+        let elapsed = target - before_timestamp;
+        let tick_cumulative = before_tick
+            .wrapping_add(after_tick.wrapping_sub(before_tick) / interval as i64 * elapsed as i64);
+        let seconds_per_liquidity_cumulative_x64 = before_seconds.wrapping_add(mul_div_floor(
+            after_seconds.wrapping_sub(before_seconds),
+            elapsed as u128,
+            interval as u128,
+        )?);
+        Ok((tick_cumulative, seconds_per_liquidity_cumulative_x64))
+    }
+
+    /// Observations either side of `target`, which must lie within the buffer
+    fn binary_search(&self, target: u64) -> (Observation, Observation) {
+        let cardinality = self.cardinality as usize;
+        let mut left = (self.index as usize + 1) % cardinality;
+        let mut right = left + cardinality - 1;
+        loop {
+            let i = (left + right) / 2;
+            let before = self.observations[i % cardinality];
+            if before.initialized == 0 {
+                left = i + 1;
+                continue;
+            }
+            let after = self.observations[(i + 1) % cardinality];
+            let before_timestamp = before.block_timestamp;
+            let after_timestamp = after.block_timestamp;
+            if before_timestamp <= target && target <= after_timestamp {
+                return (before, after);
+            }
+            if before_timestamp < target {
+                left = i + 1;
+            } else {
+                right = i - 1;
+            }
+        }
+    }
+}
@@ -1,5 +1,33 @@
 use anchor_lang::prelude::*;
 
+use crate::libraries::full_math::mul_div_floor;
+
+pub const POOL_SEED: &[u8] = b"pool_state_v1";
+pub const POOL_VAULT_0_SEED: &[u8] = b"token_0_vault";
+pub const POOL_VAULT_1_SEED: &[u8] = b"token_1_vault";
+pub const POOL_REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+
+//number of reward slots per pool
+pub const REWARD_NUM: usize = 3;
+
+//liquidity mining reward slot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct RewardInfo {
+    //reward mint, default while the slot is unused
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    //tokens emitted per second across all in-range liquidity (Q64.64)
+    pub emissions_per_second_x64: u128,
+    //rewards per unit of liquidity since the slot was created (Q64.64)
+    pub growth_global_x64: u128,
+}
+
+impl RewardInfo {
+    pub fn initialized(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+}
+
 //include ticks
 #[account]
 #[derive(InitSpace)]
@@ -13,9 +41,14 @@ pub struct LpPoolStateShape {
     //bump
     pub bump: u8,
 
-    //configuration
+    //fee tier the pool was created from
+    pub amm_config: Pubkey,
+
+    //price observation ring buffer
+    pub observation_key: Pubkey,
+
+    //configuration, fee rates are read from the fee tier on every swap
     pub tick_spacing: u16,
-    pub fee_rate: u32,
 
     //current state
     pub sqrt_price_x64: u128,
@@ -25,5 +58,63 @@ pub struct LpPoolStateShape {
     //fee tracking
     pub fee_growth_global_0: u128,
     pub fee_growth_global_1: u128,
+
+    //protocol fees waiting in the vaults, not owed to any lp
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+
+    //liquidity mining
+    pub reward_last_updated_timestamp: u64,
+    pub reward_infos: [RewardInfo; REWARD_NUM],
+}
+
+impl LpPoolStateShape {
+    /// Signer seeds of the pool PDA, which owns the vaults and mints position nfts
+    pub fn seeds(&self) -> [&[u8]; 5] {
+        [
+            POOL_SEED,
+            self.amm_config.as_ref(),
+            self.token_0_mint.as_ref(),
+            self.token_1_mint.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    /// Vault holding `mint`, default for a mint the pool does not trade
+    pub fn vault_address(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.token_0_mint {
+            self.token_0_vault_address
+        } else if *mint == self.token_1_mint {
+            self.token_1_vault_address
+        } else {
+            Pubkey::default()
+        }
+    }
+
+    /// Accrue emissions up to `now` into each reward's global growth
+    /// Must run before liquidity changes or ticks are crossed
+    pub fn update_reward_infos(&mut self, now: u64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.reward_last_updated_timestamp);
+        if elapsed > 0 && self.liquidity > 0 {
+            for reward_info in self.reward_infos.iter_mut() {
+                if !reward_info.initialized() {
+                    continue;
+                }
+                let growth_delta = mul_div_floor(
+                    reward_info.emissions_per_second_x64,
+                    elapsed as u128,
+                    self.liquidity,
+                )?;
+                reward_info.growth_global_x64 =
+                    reward_info.growth_global_x64.wrapping_add(growth_delta);
+            }
+        }
+        self.reward_last_updated_timestamp = now;
+        Ok(())
+    }
+
+    pub fn reward_growths_global(&self) -> [u128; REWARD_NUM] {
+        self.reward_infos.map(|reward_info| reward_info.growth_global_x64)
+    }
 }
 
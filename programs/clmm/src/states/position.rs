@@ -1,4 +1,24 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::errors::ClmmError;
+use crate::states::pool::REWARD_NUM;
+
+pub const POSITION_SEED: &[u8] = b"position";
+
+//actions a delegate may be allowed to take
+pub const PERMISSION_INCREASE_LIQUIDITY: u8 = 1 << 0;
+pub const PERMISSION_DECREASE_LIQUIDITY: u8 = 1 << 1;
+pub const PERMISSION_COLLECT: u8 = 1 << 2;
+pub const PERMISSION_ALL: u8 =
+    PERMISSION_INCREASE_LIQUIDITY | PERMISSION_DECREASE_LIQUIDITY | PERMISSION_COLLECT;
+
+//reward checkpoint per pool reward slot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PositionRewardInfo {
+    pub growth_inside_last_x64: u128,
+    pub amount_owed: u64,
+}
 
 //design postions
 #[account]
@@ -13,4 +33,53 @@ pub struct Position {
     pub fee_growth_inside_1_last: u128,
     pub tokens_owed_0: u64,
     pub tokens_owed_1: u64,
+    //mint of the position nft, default for owner-bound positions
+    pub nft_mint: Pubkey,
+    //manager acting on the owner's behalf, default when none
+    pub delegate: Pubkey,
+    pub delegate_permissions: u8,
+    //owner who appointed the delegate, the grant lapses once the nft changes hands
+    pub delegate_owner: Pubkey,
+    //liquidity mining checkpoints
+    pub reward_infos: [PositionRewardInfo; REWARD_NUM],
+}
+
+impl Position {
+    pub fn is_nft(&self) -> bool {
+        self.nft_mint != Pubkey::default()
+    }
+
+    /// NFT positions belong to whoever holds the token, others to the recorded owner
+    pub fn get_owner(
+        &self,
+        nft_account: Option<&InterfaceAccount<TokenAccount>>,
+    ) -> Result<Pubkey> {
+        if !self.is_nft() {
+            return Ok(self.owner);
+        }
+
+        let nft_account = nft_account.ok_or(error!(ClmmError::MissingPositionNft))?;
+        require!(
+            nft_account.mint == self.nft_mint && nft_account.amount == 1,
+            ClmmError::NotPositionOwner
+        );
+        Ok(nft_account.owner)
+    }
+
+    /// Signer must be the owner, or a delegate holding `permission`
+    /// Returns the owner, which withdrawals are bound to
+    pub fn check_authority(
+        &self,
+        signer: &Pubkey,
+        nft_account: Option<&InterfaceAccount<TokenAccount>>,
+        permission: u8,
+    ) -> Result<Pubkey> {
+        let owner = self.get_owner(nft_account)?;
+        let is_delegate = self.delegate != Pubkey::default()
+            && self.delegate == *signer
+            && self.delegate_owner == owner
+            && self.delegate_permissions & permission != 0;
+        require!(owner == *signer || is_delegate, ClmmError::NotPositionOwner);
+        Ok(owner)
+    }
 }
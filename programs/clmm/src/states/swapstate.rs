@@ -1,9 +1,239 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ClmmError;
+use crate::libraries::full_math::mul_div_floor;
+use crate::libraries::liquidity_math::{add_delta, sub_delta};
+use crate::libraries::swap_math::{
+    compute_swap_step, find_next_initialized_tick, FEE_RATE_DENOMINATOR, Q64,
+};
+use crate::libraries::tick_math::{
+    get_sqrt_price_at_tick, get_tick_at_sqrt_price, MAX_TICK, MIN_TICK,
+};
+use crate::states::pool::REWARD_NUM;
+use crate::states::tick::{TickArrayState, TICK_ARRAY_SIZE};
+
+/// Inputs that stay fixed for every step of a swap
+pub struct SwapParams {
+    pub zero_for_one: bool,
+    pub is_base_input: bool,
+    pub sqrt_price_limit_x64: u128,
+    pub tick_spacing: u16,
+    pub fee_rate: u32,
+    pub protocol_fee_rate: u32,
+    /// fee growth of the output token, it does not move during the swap
+    pub fee_growth_global_other: u128,
+    pub reward_growths_global: [u128; REWARD_NUM],
+}
+
+/// State during swap execution
+/// amount_specified_remaining is positive for exact input, negative for exact output
 pub struct SwapState {
     pub amount_specified_remaining: i64,
-    pub amount_calculated: i64,
+    pub amount_calculated: u64,
     pub sqrt_price_x64: u128,
     pub tick: i32,
     pub liquidity: u128,
-    pub fee_growth_globals: u128,
+    pub fee_growth_global: u128,
+    pub fee_amount: u64,
+    pub protocol_fee: u64,
+}
+
+impl SwapState {
+    /// Run swap steps until the amount is used up or the price limit is hit
+    /// tick_array holds the current tick, load_next(i) returns the i-th array in swap order
+    pub fn swap_across_tick_arrays<A, F>(
+        &mut self,
+        params: &SwapParams,
+        mut tick_array: A,
+        mut load_next: F,
+    ) -> Result<()>
+    where
+        A: DerefMut<Target = TickArrayState>,
+        F: FnMut(usize) -> Result<A>,
+    {
+        let zero_for_one = params.zero_for_one;
+        let tick_spacing = params.tick_spacing as i32;
+        let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing;
+        let mut array_index = 0;
+
+        while self.amount_specified_remaining != 0
+            && self.sqrt_price_x64 != params.sqrt_price_limit_x64
+        {
+            let sqrt_price_start = self.sqrt_price_x64;
+
+            // Find next initialized tick, or the edge of the current array
+            let start_tick_index = tick_array.start_tick_index;
+            let next_initialized = find_next_initialized_tick(
+                &tick_array.ticks,
+                start_tick_index,
+                self.tick,
+                tick_spacing,
+                zero_for_one,
+            );
+
+            let next_tick = match next_initialized {
+                Some((tick, _)) => tick,
+                None if zero_for_one => start_tick_index.max(MIN_TICK),
+                None => (start_tick_index + ticks_in_array).min(MAX_TICK),
+            };
+
+            // Get sqrt_price at next tick
+            let sqrt_price_next_tick = get_sqrt_price_at_tick(next_tick);
+
+            // Determine target price (capped by limit)
+            let sqrt_price_target = if zero_for_one {
+                sqrt_price_next_tick.max(params.sqrt_price_limit_x64)
+            } else {
+                sqrt_price_next_tick.min(params.sqrt_price_limit_x64)
+            };
+
+            // Compute swap step
+            let (sqrt_price_next, amount_in_step, amount_out_step, fee_amount) = compute_swap_step(
+                self.sqrt_price_x64,
+                sqrt_price_target,
+                self.liquidity,
+                self.amount_specified_remaining,
+                params.fee_rate,
+                zero_for_one,
+            )?;
+
+            // Update amounts
+            let amount_in_with_fee = amount_in_step
+                .checked_add(fee_amount)
+                .ok_or(error!(ClmmError::AmountOverflow))?;
+            if params.is_base_input {
+                self.amount_specified_remaining = self
+                    .amount_specified_remaining
+                    .checked_sub(to_i64(amount_in_with_fee)?)
+                    .ok_or(error!(ClmmError::MathUnderflow))?;
+                self.amount_calculated = self
+                    .amount_calculated
+                    .checked_add(amount_out_step)
+                    .ok_or(error!(ClmmError::AmountOverflow))?;
+            } else {
+                self.amount_specified_remaining = self
+                    .amount_specified_remaining
+                    .checked_add(to_i64(amount_out_step)?)
+                    .ok_or(error!(ClmmError::MathOverflow))?;
+                self.amount_calculated = self
+                    .amount_calculated
+                    .checked_add(amount_in_with_fee)
+                    .ok_or(error!(ClmmError::AmountOverflow))?;
+            }
+
+            // Split off the protocol share, the rest goes to lps
+            self.fee_amount = self
+                .fee_amount
+                .checked_add(fee_amount)
+                .ok_or(error!(ClmmError::AmountOverflow))?;
+            let mut lp_fee = fee_amount;
+            if params.protocol_fee_rate > 0 {
+                let protocol_fee = mul_div_floor(
+                    fee_amount as u128,
+                    params.protocol_fee_rate as u128,
+                    FEE_RATE_DENOMINATOR as u128,
+                )? as u64;
+                lp_fee = lp_fee
+                    .checked_sub(protocol_fee)
+                    .ok_or(error!(ClmmError::MathUnderflow))?;
+                self.protocol_fee = self
+                    .protocol_fee
+                    .checked_add(protocol_fee)
+                    .ok_or(error!(ClmmError::AmountOverflow))?;
+            }
+
+            // Update fee growth, the global accumulator is allowed to wrap
+            if self.liquidity > 0 && lp_fee > 0 {
+                let fee_growth_delta = mul_div_floor(lp_fee as u128, Q64, self.liquidity)?;
+                self.fee_growth_global = self.fee_growth_global.wrapping_add(fee_growth_delta);
+            }
+
+            // Update price
+            self.sqrt_price_x64 = sqrt_price_next;
+
+            // Handle tick crossing
+            let reached_next_tick = sqrt_price_next == sqrt_price_next_tick;
+            match next_initialized {
+                Some((_, tick_index_in_array)) if reached_next_tick => {
+                    let tick_state = &mut tick_array.ticks[tick_index_in_array];
+
+                    // Flip fee growth outside, the input side uses the in-flight global
+                    let (fee_growth_global_0, fee_growth_global_1) = if zero_for_one {
+                        (self.fee_growth_global, params.fee_growth_global_other)
+                    } else {
+                        (params.fee_growth_global_other, self.fee_growth_global)
+                    };
+                    tick_state.fee_growth_outside_0 =
+                        fee_growth_global_0.wrapping_sub(tick_state.fee_growth_outside_0);
+                    tick_state.fee_growth_outside_1 =
+                        fee_growth_global_1.wrapping_sub(tick_state.fee_growth_outside_1);
+
+                    // Flip reward growth outside
+                    let mut reward_growths_outside = tick_state.reward_growths_outside;
+                    for (outside, global) in reward_growths_outside
+                        .iter_mut()
+                        .zip(params.reward_growths_global)
+                    {
+                        *outside = global.wrapping_sub(*outside);
+                    }
+                    tick_state.reward_growths_outside = reward_growths_outside;
+
+                    // Update liquidity, going left subtracts liquidity_net, going right adds it
+                    let liquidity_net = tick_state.liquidity_net;
+                    self.liquidity = if zero_for_one {
+                        sub_delta(self.liquidity, liquidity_net)?
+                    } else {
+                        add_delta(self.liquidity, liquidity_net)?
+                    };
+
+                    // Update tick
+                    self.tick = if zero_for_one {
+                        next_tick - 1
+                    } else {
+                        next_tick
+                    };
+                }
+                None if reached_next_tick => {
+                    // Reached the edge of this array without crossing a tick
+                    // Sit just below the edge so the next array's first tick is still crossed
+                    self.tick = next_tick - 1;
+
+                    if self.amount_specified_remaining != 0
+                        && self.sqrt_price_x64 != params.sqrt_price_limit_x64
+                    {
+                        array_index += 1;
+                        drop(tick_array);
+                        tick_array = load_next(array_index)?;
+
+                        // Arrays must be adjacent so no initialized tick is skipped
+                        let expected_start_index = if zero_for_one {
+                            start_tick_index - ticks_in_array
+                        } else {
+                            start_tick_index + ticks_in_array
+                        };
+                        let next_start_index = tick_array.start_tick_index;
+                        require!(
+                            next_start_index == expected_start_index,
+                            ClmmError::InvalidTickArraySequence
+                        );
+                    }
+                }
+                _ if sqrt_price_next != sqrt_price_start => {
+                    // Price moved without reaching the tick, calculate new tick from price
+                    self.tick = get_tick_at_sqrt_price(self.sqrt_price_x64);
+                }
+                // A step that left the price in place keeps the tick, it may sit
+                // just below a tick that was crossed on the way down
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
 }
 
+pub fn to_i64(amount: u64) -> Result<i64> {
+    i64::try_from(amount).map_err(|_| error!(ClmmError::AmountTooLarge))
+}
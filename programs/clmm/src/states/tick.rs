@@ -1,5 +1,12 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ClmmError;
+use crate::states::pool::REWARD_NUM;
+use crate::libraries::tick_math::{MAX_TICK, MIN_TICK};
+
+pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+pub const TICK_ARRAY_SIZE: i32 = 60;
+
 //for managing the ticks
 #[zero_copy(unsafe)]
 #[repr(C, packed)]
@@ -11,6 +18,29 @@ pub struct TickState {
     pub liquidity_net: i128,
     pub fee_growth_outside_0: u128,
     pub fee_growth_outside_1: u128,
+    pub reward_growths_outside: [u128; REWARD_NUM],
+}
+
+impl TickState {
+    /// Growth below a newly initialized tick is assumed to have happened outside it
+    pub fn init_reward_growths_outside(
+        &mut self,
+        tick: i32,
+        tick_current: i32,
+        reward_growths_global: [u128; REWARD_NUM],
+    ) {
+        if tick <= tick_current {
+            self.reward_growths_outside = reward_growths_global;
+        }
+    }
+
+    /// Reset the tick once no position references it anymore
+    pub fn clear_if_unreferenced(&mut self) {
+        let liquidity_gross = self.liquidity_gross;
+        if liquidity_gross == 0 {
+            *self = TickState::default();
+        }
+    }
 }
 
 //tick state array
@@ -19,6 +49,56 @@ pub struct TickState {
 pub struct TickArrayState {
     pub pool_id: Pubkey,
     pub start_tick_index: i32,
-    pub _padding: [u8; 4],
+    pub bump: u8,
+    pub _padding: [u8; 3],
     pub ticks: [TickState; 60],
 }
+
+impl TickArrayState {
+    /// Start index of the array that holds `tick`
+    pub fn get_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+        let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+        tick.div_euclid(ticks_in_array) * ticks_in_array
+    }
+
+    /// Start index must be aligned to 60 * tick_spacing and cover a valid tick
+    pub fn is_valid_start_index(start_tick_index: i32, tick_spacing: u16) -> bool {
+        if tick_spacing == 0 {
+            return false;
+        }
+        let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+        start_tick_index % ticks_in_array == 0
+            && start_tick_index >= Self::get_array_start_index(MIN_TICK, tick_spacing)
+            && start_tick_index <= Self::get_array_start_index(MAX_TICK, tick_spacing)
+    }
+
+    /// Check the array belongs to `pool_id` and sits at its canonical pda
+    pub fn validate(&self, address: &Pubkey, pool_id: &Pubkey) -> Result<()> {
+        let array_pool_id = self.pool_id;
+        require_keys_eq!(array_pool_id, *pool_id, ClmmError::PoolMismatch);
+
+        let start_tick_index = self.start_tick_index;
+        let expected = Pubkey::create_program_address(
+            &[
+                TICK_ARRAY_SEED,
+                pool_id.as_ref(),
+                &start_tick_index.to_le_bytes(),
+                &[self.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| error!(ClmmError::InvalidTickArrayAddress))?;
+        require_keys_eq!(expected, *address, ClmmError::InvalidTickArrayAddress);
+        Ok(())
+    }
+
+    /// Index of `tick` inside this array
+    pub fn get_tick_offset(&self, tick: i32, tick_spacing: u16) -> Result<usize> {
+        let start_tick_index = self.start_tick_index;
+        require!(
+            Self::get_array_start_index(tick, tick_spacing) == start_tick_index,
+            ClmmError::TickNotInArray
+        );
+        Ok(((tick - start_tick_index) / tick_spacing as i32) as usize)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod token;
+
+pub use token::*;
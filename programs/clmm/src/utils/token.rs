@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeConfig, transfer_hook, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+};
+use anchor_spl::token_interface::{Mint, TransferChecked};
+
+use crate::errors::ClmmError;
+
+//mint extensions a pool can hold without breaking its accounting
+const SUPPORTED_MINT_EXTENSIONS: &[ExtensionType] = &[
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MintCloseAuthority,
+];
+
+/// Reject Token-2022 mints carrying extensions outside the allowlist
+/// Transfer hooks are accepted only for programs in `allowed_hook_programs`
+pub fn check_mint_extensions(
+    mint: &InterfaceAccount<Mint>,
+    allowed_hook_programs: &[Pubkey],
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension in mint_state.get_extension_types()? {
+        if extension == ExtensionType::TransferHook {
+            //a hook without a program is a no-op
+            if let Some(hook_program) = transfer_hook::get_program_id(&mint_state) {
+                require!(
+                    allowed_hook_programs.contains(&hook_program),
+                    ClmmError::UnsupportedTransferHook
+                );
+            }
+            continue;
+        }
+        require!(
+            SUPPORTED_MINT_EXTENSIONS.contains(&extension),
+            ClmmError::UnsupportedMintExtension
+        );
+    }
+    Ok(())
+}
+
+/// Drop-in for `token_interface::transfer_checked` that also resolves the extra
+/// accounts a transfer-hook mint needs from the context's remaining accounts
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+//transfer fee config of a Token-2022 mint, if any
+fn get_transfer_fee_config(mint: &InterfaceAccount<Mint>) -> Result<Option<TransferFeeConfig>> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint_state
+        .get_extension::<TransferFeeConfig>()
+        .ok()
+        .copied())
+}
+
+/// Fee withheld by the mint when `pre_fee_amount` is sent
+pub fn get_transfer_fee(mint: &InterfaceAccount<Mint>, pre_fee_amount: u64) -> Result<u64> {
+    let Some(transfer_fee_config) = get_transfer_fee_config(mint)? else {
+        return Ok(0);
+    };
+    transfer_fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, pre_fee_amount)
+        .ok_or(error!(ClmmError::AmountOverflow))
+}
+
+/// Fee to add on top so that `post_fee_amount` arrives
+pub fn get_transfer_inverse_fee(
+    mint: &InterfaceAccount<Mint>,
+    post_fee_amount: u64,
+) -> Result<u64> {
+    let Some(transfer_fee_config) = get_transfer_fee_config(mint)? else {
+        return Ok(0);
+    };
+    transfer_fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, post_fee_amount)
+        .ok_or(error!(ClmmError::AmountOverflow))
+}
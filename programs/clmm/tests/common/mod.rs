@@ -0,0 +1,9 @@
+use anchor_lang::error::Error;
+
+/// Anchor error code of a failed result
+pub fn error_code<T: std::fmt::Debug>(result: anchor_lang::Result<T>) -> u32 {
+    match result.unwrap_err() {
+        Error::AnchorError(error) => error.error_code_number,
+        Error::ProgramError(error) => panic!("unexpected program error {error:?}"),
+    }
+}
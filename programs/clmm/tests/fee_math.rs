@@ -0,0 +1,62 @@
+mod common;
+
+use clmm::errors::ClmmError;
+use clmm::libraries::liquidity_math::{add_delta, sub_delta};
+use clmm::libraries::{calculate_tokens_owed, get_fee_growth_inside};
+use common::error_code;
+
+#[test]
+fn fee_growth_inside_wraps_past_u128_max() {
+    // global has wrapped around since the outside values were recorded
+    let fee_growth_global = 5u128;
+    let outside_lower = u128::MAX - 9;
+    let outside_upper = 0;
+
+    // price inside the range: inside = global - below - above, modulo 2^128
+    let inside = get_fee_growth_inside(-60, 60, 0, fee_growth_global, outside_lower, outside_upper);
+    assert_eq!(inside, 15);
+
+    // price below the range: below = global - outside_lower
+    let inside = get_fee_growth_inside(
+        -60,
+        60,
+        -120,
+        fee_growth_global,
+        outside_lower,
+        outside_upper,
+    );
+    assert_eq!(inside, outside_lower);
+}
+
+#[test]
+fn tokens_owed_across_wrapped_growth() {
+    let one_token_per_liquidity = 1u128 << 64;
+    let last = u128::MAX - one_token_per_liquidity + 1;
+    let current = 0;
+    assert_eq!(calculate_tokens_owed(current, last, 1_000).unwrap(), 1_000);
+}
+
+#[test]
+fn liquidity_delta_is_checked() {
+    assert_eq!(add_delta(100, -40).unwrap(), 60);
+    assert_eq!(add_delta(100, 40).unwrap(), 140);
+    assert_eq!(sub_delta(100, 40).unwrap(), 60);
+    assert_eq!(sub_delta(100, -40).unwrap(), 140);
+
+    assert_eq!(
+        error_code(add_delta(10, -11)),
+        u32::from(ClmmError::MathUnderflow)
+    );
+    assert_eq!(
+        error_code(sub_delta(10, 11)),
+        u32::from(ClmmError::MathUnderflow)
+    );
+    assert_eq!(
+        error_code(add_delta(u128::MAX, 1)),
+        u32::from(ClmmError::MathOverflow)
+    );
+    assert_eq!(
+        error_code(sub_delta(u128::MAX, i128::MIN)),
+        u32::from(ClmmError::MathOverflow)
+    );
+}
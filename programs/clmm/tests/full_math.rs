@@ -0,0 +1,145 @@
+mod common;
+
+use clmm::errors::ClmmError;
+use clmm::libraries::full_math::{mul_div_ceil, mul_div_floor, mul_shr};
+use clmm::libraries::swap_math::{
+    get_amount_0_delta, get_amount_1_delta, get_next_sqrt_price_from_input, Q64,
+};
+use clmm::libraries::tick_math::{get_sqrt_price_at_tick, MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64};
+use common::error_code;
+
+#[test]
+fn mul_div_rounding() {
+    assert_eq!(mul_div_floor(7, 3, 2).unwrap(), 10);
+    assert_eq!(mul_div_ceil(7, 3, 2).unwrap(), 11);
+
+    // exact results are not bumped by the ceiling
+    assert_eq!(mul_div_floor(6, 2, 3).unwrap(), 4);
+    assert_eq!(mul_div_ceil(6, 2, 3).unwrap(), 4);
+
+    // the product overflows u128 but the quotient does not
+    assert_eq!(
+        mul_div_floor(u128::MAX, u128::MAX, u128::MAX).unwrap(),
+        u128::MAX
+    );
+    assert_eq!(
+        mul_div_ceil(u128::MAX, u128::MAX - 1, u128::MAX).unwrap(),
+        u128::MAX - 1
+    );
+}
+
+#[test]
+fn mul_div_errors() {
+    assert_eq!(
+        error_code(mul_div_floor(u128::MAX, 2, 1)),
+        u32::from(ClmmError::MulDivOverflow)
+    );
+    assert_eq!(
+        error_code(mul_div_ceil(u128::MAX, u128::MAX, u128::MAX - 1)),
+        u32::from(ClmmError::MulDivOverflow)
+    );
+    assert_eq!(
+        error_code(mul_div_floor(1, 1, 0)),
+        u32::from(ClmmError::DivisionByZero)
+    );
+    assert_eq!(
+        error_code(mul_div_ceil(1, 1, 0)),
+        u32::from(ClmmError::DivisionByZero)
+    );
+}
+
+#[test]
+fn mul_shr_rounds_down() {
+    // 3 × 2^63 >> 64 = 1.5
+    assert_eq!(mul_shr(3, 1 << 63, 64).unwrap(), 1);
+    assert_eq!(mul_shr(u128::MAX, Q64, 64).unwrap(), u128::MAX);
+    assert_eq!(
+        error_code(mul_shr(u128::MAX, u128::MAX, 64)),
+        u32::from(ClmmError::MulDivOverflow)
+    );
+}
+
+#[test]
+fn amount_deltas_across_full_range() {
+    // Δy = L × (upper - lower) / 2^64, exact with a power of two liquidity
+    let liquidity = 1u128 << 31;
+    let expected_1 = ((MAX_SQRT_PRICE_X64 - MIN_SQRT_PRICE_X64) >> 33) as u64;
+    let floor_1 =
+        get_amount_1_delta(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64, liquidity, false).unwrap();
+    let ceil_1 =
+        get_amount_1_delta(MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64, liquidity, true).unwrap();
+    assert_eq!(floor_1, expected_1);
+    assert!(ceil_1 - floor_1 <= 1);
+
+    // Δx is bounded by L × 2^64 / lower and dominated by it across the full range
+    let liquidity = 1_000_000_000u128;
+    let bound_0 = mul_div_floor(liquidity, Q64, MIN_SQRT_PRICE_X64).unwrap() as u64;
+    let floor_0 =
+        get_amount_0_delta(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64, liquidity, false).unwrap();
+    let ceil_0 =
+        get_amount_0_delta(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64, liquidity, true).unwrap();
+    assert!(floor_0 <= bound_0 && bound_0 - floor_0 <= 1);
+    assert!(ceil_0 - floor_0 <= 1);
+}
+
+#[test]
+fn amount_deltas_overflow_with_huge_liquidity() {
+    for round_up in [false, true] {
+        assert_eq!(
+            error_code(get_amount_0_delta(
+                MIN_SQRT_PRICE_X64,
+                MAX_SQRT_PRICE_X64,
+                u128::MAX,
+                round_up
+            )),
+            u32::from(ClmmError::AmountOverflow)
+        );
+        assert_eq!(
+            error_code(get_amount_1_delta(
+                MIN_SQRT_PRICE_X64,
+                MAX_SQRT_PRICE_X64,
+                u128::MAX,
+                round_up
+            )),
+            u32::from(ClmmError::AmountOverflow)
+        );
+    }
+}
+
+#[test]
+fn next_sqrt_price_from_input_round_trips() {
+    let liquidity = 1u128 << 80;
+    let prices = [
+        get_sqrt_price_at_tick(-400_000),
+        get_sqrt_price_at_tick(-1_000),
+        Q64,
+        get_sqrt_price_at_tick(1_000),
+        get_sqrt_price_at_tick(400_000),
+    ];
+    let amounts = [1u64, 1_000, 1_000_000_000, 1 << 40];
+
+    for sqrt_price in prices {
+        // no input, no movement
+        for zero_for_one in [true, false] {
+            assert_eq!(
+                get_next_sqrt_price_from_input(sqrt_price, liquidity, 0, zero_for_one).unwrap(),
+                sqrt_price
+            );
+        }
+
+        for amount_in in amounts {
+            // price is rounded against the trader, the move never costs more than was paid
+            let lower =
+                get_next_sqrt_price_from_input(sqrt_price, liquidity, amount_in, true).unwrap();
+            assert!(lower <= sqrt_price);
+            let needed = get_amount_0_delta(lower, sqrt_price, liquidity, true).unwrap();
+            assert!(needed <= amount_in, "token 0 {sqrt_price} {amount_in}");
+
+            let upper =
+                get_next_sqrt_price_from_input(sqrt_price, liquidity, amount_in, false).unwrap();
+            assert!(upper >= sqrt_price);
+            let needed = get_amount_1_delta(sqrt_price, upper, liquidity, true).unwrap();
+            assert!(needed <= amount_in, "token 1 {sqrt_price} {amount_in}");
+        }
+    }
+}
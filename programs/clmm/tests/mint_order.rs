@@ -0,0 +1,31 @@
+use anchor_lang::prelude::Pubkey;
+use clmm::libraries::{is_pool_order, sort_mints};
+
+fn mints() -> (Pubkey, Pubkey) {
+    let a = Pubkey::new_from_array([1; 32]);
+    let b = Pubkey::new_from_array([2; 32]);
+    assert!(a < b);
+    (a, b)
+}
+
+#[test]
+fn ordered_mints_are_kept() {
+    let (a, b) = mints();
+    assert_eq!(sort_mints(a, b), (a, b, false));
+    assert!(is_pool_order(&a, &b));
+}
+
+#[test]
+fn reversed_mints_are_swapped_and_price_inverted() {
+    let (a, b) = mints();
+    assert_eq!(sort_mints(b, a), (a, b, true));
+    // pool creation refuses the pair as given
+    assert!(!is_pool_order(&b, &a));
+}
+
+#[test]
+fn equal_mints_are_rejected() {
+    let (a, _) = mints();
+    let (token_0_mint, token_1_mint, _) = sort_mints(a, a);
+    assert!(!is_pool_order(&token_0_mint, &token_1_mint));
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::Pubkey;
+use clmm::libraries::get_arithmetic_mean_tick;
+use clmm::states::ObservationState;
+
+fn new_observation_state(timestamp: u64, cardinality_next: u16) -> Box<ObservationState> {
+    let mut state: Box<ObservationState> = Box::new(bytemuck::Zeroable::zeroed());
+    state.initialize(Pubkey::default(), 255, timestamp, 1);
+    state.cardinality_next = cardinality_next;
+    state
+}
+
+#[test]
+fn observe_interpolates_between_observations() {
+    let mut state = new_observation_state(1_000, 4);
+    // tick 10 for 10s, then tick -20 for 20s
+    state.write(1_010, 2, 10, 1 << 20);
+    state.write(1_030, 3, -20, 1 << 20);
+
+    let now = 1_040;
+    let tick = 5;
+    let liquidity = 1 << 20;
+    let observe = |seconds_ago| {
+        state
+            .observe_single(now, seconds_ago, tick, liquidity)
+            .unwrap()
+            .0
+    };
+
+    assert_eq!(observe(40), 0);
+    assert_eq!(observe(35), 50);
+    assert_eq!(observe(30), 100);
+    assert_eq!(observe(20), 100 - 200);
+    assert_eq!(observe(10), 100 - 400);
+    assert_eq!(observe(0), 100 - 400 + 50);
+    assert!(state.observe_single(now, 41, tick, liquidity).is_err());
+}
+
+#[test]
+fn write_once_per_slot_and_grow_on_wrap() {
+    let mut state = new_observation_state(1_000, 1);
+    state.write(1_010, 2, 10, 1);
+    // a ring of one only keeps the newest observation
+    assert_eq!({ state.index }, 0);
+    assert!(state.observe_single(1_010, 5, 10, 1).is_err());
+
+    state.cardinality_next = 3;
+    state.write(1_020, 3, 10, 1);
+    // a second swap in the same slot is not recorded
+    state.write(1_025, 3, 10, 1);
+    assert_eq!({ state.index }, 1);
+    assert_eq!({ state.cardinality }, 3);
+    assert_eq!(state.observe_single(1_020, 10, 10, 1).unwrap().0, 100);
+}
+
+#[test]
+fn arithmetic_mean_tick_rounds_down() {
+    assert_eq!(get_arithmetic_mean_tick(0, 100, 10).unwrap(), 10);
+    assert_eq!(get_arithmetic_mean_tick(0, 105, 10).unwrap(), 10);
+    assert_eq!(get_arithmetic_mean_tick(0, -105, 10).unwrap(), -11);
+    assert_eq!(get_arithmetic_mean_tick(50, -50, 10).unwrap(), -10);
+    assert!(get_arithmetic_mean_tick(0, 100, 0).is_err());
+}
@@ -0,0 +1,171 @@
+use clmm::libraries::full_math::mul_div_ceil;
+use clmm::libraries::swap_math::{
+    compute_swap_step, get_amount_0_delta, get_amount_1_delta, FEE_RATE_DENOMINATOR,
+};
+use clmm::libraries::tick_math::get_sqrt_price_at_tick;
+
+const LIQUIDITY: u128 = 1_000_000_000_000_000_000;
+const FEE_RATE: u32 = 3_000;
+
+// current price at tick 0, target `ticks` away in the swap direction
+fn prices(zero_for_one: bool, ticks: i32) -> (u128, u128) {
+    let target_tick = if zero_for_one { -ticks } else { ticks };
+    (
+        get_sqrt_price_at_tick(0),
+        get_sqrt_price_at_tick(target_tick),
+    )
+}
+
+// fee charged on top of a net input, over the fee complement
+fn fee_on(amount_in: u64) -> u64 {
+    mul_div_ceil(
+        amount_in as u128,
+        FEE_RATE as u128,
+        (FEE_RATE_DENOMINATOR - FEE_RATE) as u128,
+    )
+    .unwrap() as u64
+}
+
+#[test]
+fn exact_out_caps_output_below_target() {
+    for zero_for_one in [true, false] {
+        let (current, target) = prices(zero_for_one, 1_000);
+        let (sqrt_price_next, amount_in, amount_out, fee_amount) = compute_swap_step(
+            current,
+            target,
+            LIQUIDITY,
+            -1_000_000,
+            FEE_RATE,
+            zero_for_one,
+        )
+        .unwrap();
+
+        assert_ne!(sqrt_price_next, target);
+        assert_eq!(amount_out, 1_000_000);
+        assert!(amount_in > 0);
+        assert_eq!(fee_amount, fee_on(amount_in));
+    }
+}
+
+#[test]
+fn exact_out_stops_at_target() {
+    for zero_for_one in [true, false] {
+        let (current, target) = prices(zero_for_one, 10);
+        let amount_remaining = 1_000_000_000_000_000i64;
+        let (sqrt_price_next, amount_in, amount_out, fee_amount) = compute_swap_step(
+            current,
+            target,
+            LIQUIDITY,
+            -amount_remaining,
+            FEE_RATE,
+            zero_for_one,
+        )
+        .unwrap();
+
+        assert_eq!(sqrt_price_next, target);
+        let (expected_in, expected_out) = if zero_for_one {
+            (
+                get_amount_0_delta(target, current, LIQUIDITY, true).unwrap(),
+                get_amount_1_delta(target, current, LIQUIDITY, false).unwrap(),
+            )
+        } else {
+            (
+                get_amount_1_delta(current, target, LIQUIDITY, true).unwrap(),
+                get_amount_0_delta(current, target, LIQUIDITY, false).unwrap(),
+            )
+        };
+        assert_eq!(amount_in, expected_in);
+        assert_eq!(amount_out, expected_out);
+        assert!(amount_out < amount_remaining as u64);
+        assert_eq!(fee_amount, fee_on(amount_in));
+    }
+}
+
+#[test]
+fn exact_in_reaching_and_not_reaching_target() {
+    for zero_for_one in [true, false] {
+        // not reached: the whole input is spent, the remainder after amount_in is fee
+        let (current, target) = prices(zero_for_one, 1_000);
+        let (sqrt_price_next, amount_in, _, fee_amount) = compute_swap_step(
+            current,
+            target,
+            LIQUIDITY,
+            1_000_000,
+            FEE_RATE,
+            zero_for_one,
+        )
+        .unwrap();
+        assert_ne!(sqrt_price_next, target);
+        assert_eq!(amount_in + fee_amount, 1_000_000);
+
+        // reached: fee is charged over the complement and some input is left over
+        let (current, target) = prices(zero_for_one, 10);
+        let amount_remaining = 1_000_000_000_000_000i64;
+        let (sqrt_price_next, amount_in, _, fee_amount) = compute_swap_step(
+            current,
+            target,
+            LIQUIDITY,
+            amount_remaining,
+            FEE_RATE,
+            zero_for_one,
+        )
+        .unwrap();
+        assert_eq!(sqrt_price_next, target);
+        assert_eq!(fee_amount, fee_on(amount_in));
+        assert!(amount_in + fee_amount < amount_remaining as u64);
+    }
+}
+
+#[test]
+fn exact_out_of_exact_in_result_costs_no_more() {
+    for zero_for_one in [true, false] {
+        let (current, target) = prices(zero_for_one, 1_000);
+        for amount in [1_000i64, 1_000_000, 1_000_000_000_000] {
+            let (price_in, amount_in, amount_out, fee_amount) =
+                compute_swap_step(current, target, LIQUIDITY, amount, FEE_RATE, zero_for_one)
+                    .unwrap();
+
+            // buying back exactly what exact input produced
+            let (price_out, amount_in_back, amount_out_back, fee_amount_back) = compute_swap_step(
+                current,
+                target,
+                LIQUIDITY,
+                -(amount_out as i64),
+                FEE_RATE,
+                zero_for_one,
+            )
+            .unwrap();
+
+            assert_eq!(amount_out_back, amount_out);
+            assert!(amount_in_back + fee_amount_back <= amount_in + fee_amount);
+            // the exact output step moves the price no further than the exact input one
+            if zero_for_one {
+                assert!(price_out >= price_in);
+            } else {
+                assert!(price_out <= price_in);
+            }
+        }
+    }
+}
+
+#[test]
+fn target_beyond_u64_is_not_reached() {
+    // reaching the target would take more than u64::MAX of input, a small trade still fills
+    let current = get_sqrt_price_at_tick(-138_180);
+    let target = get_sqrt_price_at_tick(-141_780);
+    let liquidity = 100_000_000_000_000_000u128;
+    assert!(get_amount_0_delta(target, current, liquidity, true).is_err());
+
+    let (sqrt_price_next, amount_in, amount_out, fee_amount) =
+        compute_swap_step(current, target, liquidity, 1_000, FEE_RATE, true).unwrap();
+    // the input is too small to move the price at this liquidity, all of it is fee
+    assert!(sqrt_price_next <= current && sqrt_price_next > target);
+    assert_eq!(amount_in + fee_amount, 1_000);
+    assert!(amount_out <= amount_in);
+
+    // exact output against the same step
+    let (sqrt_price_next, _, amount_out, _) =
+        compute_swap_step(target, current, liquidity, -1_000, FEE_RATE, false).unwrap();
+    assert!(sqrt_price_next > target && sqrt_price_next < current);
+    assert_eq!(amount_out, 1_000);
+}
@@ -0,0 +1,99 @@
+use clmm::errors::ClmmError;
+use clmm::libraries::tick_math::get_sqrt_price_at_tick;
+use clmm::states::pool::REWARD_NUM;
+use clmm::states::swapstate::{SwapParams, SwapState};
+use clmm::states::tick::TickArrayState;
+
+const LIQUIDITY: u128 = 1_000_000_000_000_000_000;
+
+fn tick_array(start_tick_index: i32) -> Box<TickArrayState> {
+    let mut tick_array: Box<TickArrayState> = Box::new(bytemuck::Zeroable::zeroed());
+    tick_array.start_tick_index = start_tick_index;
+    tick_array
+}
+
+fn swap_state(tick: i32, sqrt_price_x64: u128, liquidity: u128, amount: i64) -> SwapState {
+    SwapState {
+        amount_specified_remaining: amount,
+        amount_calculated: 0,
+        sqrt_price_x64,
+        tick,
+        liquidity,
+        fee_growth_global: 0,
+        fee_amount: 0,
+        protocol_fee: 0,
+    }
+}
+
+fn params_down_to(sqrt_price_limit_x64: u128) -> SwapParams {
+    SwapParams {
+        zero_for_one: true,
+        is_base_input: true,
+        sqrt_price_limit_x64,
+        tick_spacing: 1,
+        fee_rate: 3_000,
+        protocol_fee_rate: 0,
+        fee_growth_global_other: 0,
+        reward_growths_global: [0; REWARD_NUM],
+    }
+}
+
+#[test]
+fn dust_step_after_downward_cross_keeps_tick_below_crossed_tick() {
+    // tick -30 is the lower tick of a position holding half the liquidity
+    let crossed_tick = -30;
+    let mut array = tick_array(-60);
+    let tick_state = &mut array.ticks[30];
+    tick_state.initialized = 1;
+    tick_state.liquidity_gross = LIQUIDITY / 2;
+    tick_state.liquidity_net = (LIQUIDITY / 2) as i128;
+
+    // first swap stops exactly on the tick and crosses it
+    let mut state = swap_state(
+        -20,
+        get_sqrt_price_at_tick(-20),
+        LIQUIDITY,
+        1_000_000_000_000_000_000,
+    );
+    let params = params_down_to(get_sqrt_price_at_tick(crossed_tick));
+    state
+        .swap_across_tick_arrays(&params, &mut *array, |_| {
+            Err(ClmmError::NotEnoughTickArrays.into())
+        })
+        .unwrap();
+    assert_eq!(state.sqrt_price_x64, get_sqrt_price_at_tick(crossed_tick));
+    assert_eq!(state.tick, crossed_tick - 1);
+    assert_eq!(state.liquidity, LIQUIDITY / 2);
+
+    // a dust swap is all fee and leaves the price on the crossed tick
+    let mut state = swap_state(state.tick, state.sqrt_price_x64, state.liquidity, 1);
+    let params = params_down_to(get_sqrt_price_at_tick(-50));
+    state
+        .swap_across_tick_arrays(&params, &mut *array, |_| {
+            Err(ClmmError::NotEnoughTickArrays.into())
+        })
+        .unwrap();
+    assert_eq!(state.amount_specified_remaining, 0);
+    assert_eq!(state.fee_amount, 1);
+    assert_eq!(state.sqrt_price_x64, get_sqrt_price_at_tick(crossed_tick));
+    // the tick still matches the liquidity, which no longer includes the crossed tick
+    assert_eq!(state.tick, crossed_tick - 1);
+    assert_eq!(state.liquidity, LIQUIDITY / 2);
+}
+
+#[test]
+fn partial_step_recomputes_tick_from_price() {
+    let array = &mut *tick_array(-60);
+    let mut state = swap_state(-1, get_sqrt_price_at_tick(0), LIQUIDITY, 1_000_000_000_000);
+    let params = params_down_to(get_sqrt_price_at_tick(-50));
+    state
+        .swap_across_tick_arrays(&params, array, |_| {
+            Err(ClmmError::NotEnoughTickArrays.into())
+        })
+        .unwrap();
+
+    assert_eq!(state.amount_specified_remaining, 0);
+    assert!(state.sqrt_price_x64 < get_sqrt_price_at_tick(0));
+    assert!(state.sqrt_price_x64 >= get_sqrt_price_at_tick(state.tick));
+    assert!(state.sqrt_price_x64 < get_sqrt_price_at_tick(state.tick + 1));
+}
@@ -0,0 +1,69 @@
+use clmm::libraries::tick_math::{MAX_TICK, MIN_TICK};
+use clmm::states::tick::{TickArrayState, TICK_ARRAY_SIZE};
+
+const TICK_SPACING: u16 = 10;
+const TICKS_IN_ARRAY: i32 = TICK_ARRAY_SIZE * TICK_SPACING as i32;
+
+#[test]
+fn start_index_must_be_aligned() {
+    assert!(TickArrayState::is_valid_start_index(0, TICK_SPACING));
+    assert!(TickArrayState::is_valid_start_index(
+        TICKS_IN_ARRAY,
+        TICK_SPACING
+    ));
+    // a multiple of the tick spacing is not enough
+    assert!(!TickArrayState::is_valid_start_index(
+        TICK_SPACING as i32,
+        TICK_SPACING
+    ));
+    assert!(!TickArrayState::is_valid_start_index(100, TICK_SPACING));
+    assert!(!TickArrayState::is_valid_start_index(0, 0));
+}
+
+#[test]
+fn negative_start_index_must_be_aligned() {
+    assert!(TickArrayState::is_valid_start_index(
+        -TICKS_IN_ARRAY,
+        TICK_SPACING
+    ));
+    // remainders are negative below zero and still rejected
+    assert!(!TickArrayState::is_valid_start_index(
+        -TICKS_IN_ARRAY / 2,
+        TICK_SPACING
+    ));
+    assert!(!TickArrayState::is_valid_start_index(
+        -TICKS_IN_ARRAY - TICK_SPACING as i32,
+        TICK_SPACING
+    ));
+}
+
+#[test]
+fn min_tick_array_boundary() {
+    // the array holding MIN_TICK starts below it
+    let min_start = TickArrayState::get_array_start_index(MIN_TICK, TICK_SPACING);
+    assert_eq!(min_start, -444_000);
+    assert!(min_start <= MIN_TICK && MIN_TICK < min_start + TICKS_IN_ARRAY);
+    assert!(TickArrayState::is_valid_start_index(
+        min_start,
+        TICK_SPACING
+    ));
+    assert!(!TickArrayState::is_valid_start_index(
+        min_start - TICKS_IN_ARRAY,
+        TICK_SPACING
+    ));
+}
+
+#[test]
+fn max_tick_array_boundary() {
+    let max_start = TickArrayState::get_array_start_index(MAX_TICK, TICK_SPACING);
+    assert_eq!(max_start, 443_400);
+    assert!(max_start <= MAX_TICK && MAX_TICK < max_start + TICKS_IN_ARRAY);
+    assert!(TickArrayState::is_valid_start_index(
+        max_start,
+        TICK_SPACING
+    ));
+    assert!(!TickArrayState::is_valid_start_index(
+        max_start + TICKS_IN_ARRAY,
+        TICK_SPACING
+    ));
+}
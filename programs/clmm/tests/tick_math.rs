@@ -0,0 +1,67 @@
+mod common;
+
+use clmm::errors::ClmmError;
+use clmm::libraries::tick_math::{
+    check_ticks_range, get_sqrt_price_at_tick, get_tick_at_sqrt_price, MAX_SQRT_PRICE_X64,
+    MAX_TICK, MIN_SQRT_PRICE_X64, MIN_TICK,
+};
+use common::error_code;
+
+#[test]
+fn sqrt_price_bounds_match_tick_bounds() {
+    assert_eq!(get_sqrt_price_at_tick(MIN_TICK), MIN_SQRT_PRICE_X64);
+    assert_eq!(get_sqrt_price_at_tick(MAX_TICK), MAX_SQRT_PRICE_X64);
+    assert_eq!(get_sqrt_price_at_tick(0), 1u128 << 64);
+}
+
+#[test]
+fn tick_at_boundary_prices() {
+    assert_eq!(get_tick_at_sqrt_price(MIN_SQRT_PRICE_X64), MIN_TICK);
+    assert_eq!(get_tick_at_sqrt_price(MIN_SQRT_PRICE_X64 + 1), MIN_TICK);
+    assert_eq!(get_tick_at_sqrt_price(MAX_SQRT_PRICE_X64), MAX_TICK);
+    assert_eq!(get_tick_at_sqrt_price(MAX_SQRT_PRICE_X64 - 1), MAX_TICK - 1);
+    assert_eq!(get_tick_at_sqrt_price(1u128 << 64), 0);
+    assert_eq!(get_tick_at_sqrt_price((1u128 << 64) - 1), -1);
+}
+
+#[test]
+fn round_trips_every_tick() {
+    let mut previous = 0;
+    for tick in MIN_TICK..=MAX_TICK {
+        let sqrt_price = get_sqrt_price_at_tick(tick);
+        assert!(
+            tick == MIN_TICK || sqrt_price > previous,
+            "not monotonic at {tick}"
+        );
+        previous = sqrt_price;
+
+        assert_eq!(get_tick_at_sqrt_price(sqrt_price), tick);
+        if tick > MIN_TICK {
+            assert_eq!(get_tick_at_sqrt_price(sqrt_price - 1), tick - 1);
+        }
+        if tick < MAX_TICK {
+            assert_eq!(get_tick_at_sqrt_price(sqrt_price + 1), tick);
+        }
+    }
+}
+
+#[test]
+fn tick_range_validation() {
+    assert!(check_ticks_range(-60, 60, 60).is_ok());
+    assert!(check_ticks_range(MIN_TICK, MAX_TICK, 1).is_ok());
+
+    let cases = [
+        ((60, -60, 60), ClmmError::TickInvalidOrder),
+        ((60, 60, 60), ClmmError::TickInvalidOrder),
+        ((MIN_TICK - 1, 0, 1), ClmmError::TickLowerOverflow),
+        ((0, MAX_TICK + 1, 1), ClmmError::TickUpperOverflow),
+        ((-50, 60, 60), ClmmError::TickAndSpacingNotMatch),
+        ((0, 70, 60), ClmmError::TickAndSpacingNotMatch),
+    ];
+    for ((tick_lower, tick_upper, tick_spacing), expected) in cases {
+        assert_eq!(
+            error_code(check_ticks_range(tick_lower, tick_upper, tick_spacing)),
+            u32::from(expected)
+        );
+    }
+}